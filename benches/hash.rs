@@ -0,0 +1,28 @@
+// One-shot hashing throughput. To check a change, save a baseline before it and compare after:
+//   cargo bench --bench hash -- --save-baseline before
+//   cargo bench --bench hash -- --baseline before
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+
+const SIZES: [usize; 3] = [16, 1024, 64 * 1024];
+
+fn bench_hash(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash");
+    for size in SIZES {
+        let message = vec![0x61u8; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("sha1", size), &message, |b, m| {
+            b.iter(|| sha::sha1::hash(black_box(m)))
+        });
+        group.bench_with_input(BenchmarkId::new("sha256", size), &message, |b, m| {
+            b.iter(|| sha::sha256::hash(black_box(m)))
+        });
+        group.bench_with_input(BenchmarkId::new("sha512", size), &message, |b, m| {
+            b.iter(|| sha::sha512::hash(black_box(m)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_hash);
+criterion_main!(benches);
@@ -0,0 +1,35 @@
+#![no_main]
+// Feeds the input to the streaming hashers in pieces and checks they agree with the one-shot `hash`.
+// Input layout: the first byte is how many split lengths follow, then that many bytes of split
+// lengths (used in a cycle), and everything after that is the message.
+use libfuzzer_sys::fuzz_target;
+use sha::{sha1, sha256, sha512};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&split_count, rest)) = data.split_first() else {
+        return;
+    };
+    let split_count = (split_count as usize).min(rest.len());
+    let (splits, message) = rest.split_at(split_count);
+
+    let mut sha1_hasher = sha1::Sha1::new();
+    let mut sha256_hasher = sha256::Sha256::new();
+    let mut sha512_hasher = sha512::Sha512::new();
+
+    let mut remaining = message;
+    let mut sizes = splits.iter().cycle();
+    while !remaining.is_empty() {
+        // with no split lengths the whole message goes in one update, a split length of 0 still takes one byte
+        // so every pass makes progress
+        let size = sizes.next().map_or(remaining.len(), |&s| s as usize).max(1).min(remaining.len());
+        let (piece, tail) = remaining.split_at(size);
+        sha1_hasher.update(piece);
+        sha256_hasher.update(piece);
+        sha512_hasher.update(piece);
+        remaining = tail;
+    }
+
+    assert_eq!(sha1_hasher.finalize(), sha1::hash(message));
+    assert_eq!(sha256_hasher.finalize(), sha256::hash(message));
+    assert_eq!(sha512_hasher.finalize(), sha512::hash(message));
+});
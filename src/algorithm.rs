@@ -0,0 +1,189 @@
+use crate::digest::DynDigest;
+use crate::error::ShaError;
+use crate::{sha1, sha256, sha512};
+
+/// The hash algorithms in this crate, for picking one at runtime (from a config file or command line for example)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl Algorithm {
+    /// Look up an algorithm by name, like `sha256` or `SHA-256`
+    pub fn from_name(name: &str) -> Result<Self, ShaError> {
+        match name.to_ascii_lowercase().replace('-', "").as_str() {
+            "sha1" => Ok(Algorithm::Sha1),
+            "sha256" => Ok(Algorithm::Sha256),
+            "sha512" => Ok(Algorithm::Sha512),
+            _ => Err(ShaError::UnknownAlgorithm),
+        }
+    }
+}
+
+/// Hash a message with whichever algorithm `alg` is, returning the standard digest bytes
+/// The length of the result depends on the algorithm (20, 32 or 64 bytes)
+pub fn hash_with(alg: Algorithm, message: &[u8]) -> Vec<u8> {
+    match alg {
+        Algorithm::Sha1 => sha1::hash_bytes(message).to_vec(),
+        Algorithm::Sha256 => sha256::hash_bytes(message).to_vec(),
+        Algorithm::Sha512 => sha512::hash_bytes(message).to_vec(),
+    }
+}
+
+/// A streaming hasher for whichever algorithm `alg` is, behind a trait object
+/// ```
+/// use sha::algorithm::{boxed_hasher, Algorithm};
+///
+/// let mut hasher = boxed_hasher(Algorithm::from_name("sha256").unwrap());
+/// hasher.update(b"hello world");
+/// assert_eq!(hasher.finalize_boxed().len(), 32);
+/// ```
+pub fn boxed_hasher(alg: Algorithm) -> Box<dyn DynDigest> {
+    match alg {
+        Algorithm::Sha1 => Box::new(sha1::Sha1::new()),
+        Algorithm::Sha256 => Box::new(sha256::Sha256::new()),
+        Algorithm::Sha512 => Box::new(sha512::Sha512::new()),
+    }
+}
+
+/// Several hashers fed from one pass over the data, for when a file needs more than one digest
+/// (mirror manifests with both SHA-1 and SHA-256 for example). Only the algorithms asked for are computed
+/// ```
+/// use sha::algorithm::{Algorithm, MultiHasher};
+///
+/// let mut hasher = MultiHasher::new(&[Algorithm::Sha1, Algorithm::Sha256]);
+/// hasher.update(b"hello world");
+/// let digests = hasher.finalize();
+/// assert!(digests.sha1.is_some() && digests.sha256.is_some() && digests.sha512.is_none());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MultiHasher {
+    sha1: Option<sha1::Sha1>,
+    sha256: Option<sha256::Sha256>,
+    sha512: Option<sha512::Sha512>,
+}
+
+/// The digest bytes from a `MultiHasher`, `None` for the algorithms it wasn't asked for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MultiDigest {
+    pub sha1: Option<[u8; 20]>,
+    pub sha256: Option<[u8; 32]>,
+    pub sha512: Option<[u8; 64]>,
+}
+
+impl MultiHasher {
+    /// Hash with each algorithm in `algorithms`, listing one twice is the same as listing it once
+    pub fn new(algorithms: &[Algorithm]) -> Self {
+        let mut hasher = Self::default();
+        for alg in algorithms {
+            match alg {
+                Algorithm::Sha1 => hasher.sha1 = Some(sha1::Sha1::new()),
+                Algorithm::Sha256 => hasher.sha256 = Some(sha256::Sha256::new()),
+                Algorithm::Sha512 => hasher.sha512 = Some(sha512::Sha512::new()),
+            }
+        }
+        hasher
+    }
+
+    /// Add more of the message to every hasher
+    pub fn update(&mut self, data: &[u8]) {
+        if let Some(hasher) = &mut self.sha1 {
+            hasher.update(data);
+        }
+        if let Some(hasher) = &mut self.sha256 {
+            hasher.update(data);
+        }
+        if let Some(hasher) = &mut self.sha512 {
+            hasher.update(data);
+        }
+    }
+
+    /// Finish all the hashes
+    pub fn finalize(self) -> MultiDigest {
+        MultiDigest {
+            sha1: self.sha1.map(|hasher| sha1::state_to_bytes(&hasher.finalize())),
+            sha256: self.sha256.map(|hasher| sha256::state_to_bytes(&hasher.finalize())),
+            sha512: self.sha512.map(|hasher| sha512::state_to_bytes(&hasher.finalize())),
+        }
+    }
+}
+
+/// So a reader can be hashed with `std::io::copy`
+#[cfg(feature = "std")]
+impl std::io::Write for MultiHasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_name_test() {
+        assert_eq!(Algorithm::from_name("sha1").unwrap(), Algorithm::Sha1);
+        assert_eq!(Algorithm::from_name("SHA-1").unwrap(), Algorithm::Sha1);
+        assert_eq!(Algorithm::from_name("sha256").unwrap(), Algorithm::Sha256);
+        assert_eq!(Algorithm::from_name("SHA-256").unwrap(), Algorithm::Sha256);
+        assert_eq!(Algorithm::from_name("sha512").unwrap(), Algorithm::Sha512);
+        assert_eq!(Algorithm::from_name("Sha-512").unwrap(), Algorithm::Sha512);
+        assert!(matches!(Algorithm::from_name("md5"), Err(ShaError::UnknownAlgorithm)));
+    }
+
+    #[test]
+    fn hash_with_test() {
+        let message = b"hello world";
+        assert_eq!(hash_with(Algorithm::Sha1, message), sha1::hash_bytes(message));
+        assert_eq!(hash_with(Algorithm::Sha256, message), sha256::hash_bytes(message));
+        assert_eq!(hash_with(Algorithm::Sha512, message), sha512::hash_bytes(message));
+    }
+
+    #[test]
+    fn boxed_hasher_test() {
+        let mut hasher = boxed_hasher(Algorithm::Sha512);
+        hasher.update(b"hello ");
+        hasher.update(b"world");
+        let digest = hasher.finalize_boxed();
+        assert_eq!(digest.len(), 64);
+        assert_eq!(digest, sha512::hash_bytes(b"hello world"));
+    }
+
+    #[test]
+    fn multi_hasher_test() {
+        let message = b"a".repeat(1000);
+        let mut hasher = MultiHasher::new(&[Algorithm::Sha1, Algorithm::Sha256, Algorithm::Sha512]);
+        for piece in message.chunks(77) {
+            hasher.update(piece);
+        }
+        assert_eq!(hasher.finalize(), MultiDigest {
+            sha1: Some(sha1::hash_bytes(&message)),
+            sha256: Some(sha256::hash_bytes(&message)),
+            sha512: Some(sha512::hash_bytes(&message)),
+        });
+
+        let mut only_sha256 = MultiHasher::new(&[Algorithm::Sha256, Algorithm::Sha256]);
+        only_sha256.update(&message);
+        let digests = only_sha256.finalize();
+        assert_eq!(digests.sha256, Some(sha256::hash_bytes(&message)));
+        assert_eq!((digests.sha1, digests.sha512), (None, None));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn multi_hasher_copy_test() {
+        let message = b"hello world".repeat(100);
+        let mut hasher = MultiHasher::new(&[Algorithm::Sha1, Algorithm::Sha256]);
+        std::io::copy(&mut &message[..], &mut hasher).unwrap();
+        let digests = hasher.finalize();
+        assert_eq!(digests.sha1, Some(sha1::hash_bytes(&message)));
+        assert_eq!(digests.sha256, Some(sha256::hash_bytes(&message)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,25 @@
+/// Split `data` into whole `N` byte blocks, as array references so the compression functions
+/// don't need any length checks. A partial block at the end is left out, it's the caller's job to buffer it
+pub(crate) fn blocks<const N: usize>(data: &[u8]) -> impl Iterator<Item = &[u8; N]> {
+    data.chunks_exact(N).map(|block| block.try_into().unwrap())
+}
+
+/// Whatever is left over after `blocks` has taken the whole blocks
+pub(crate) fn remainder<const N: usize>(data: &[u8]) -> &[u8] {
+    &data[data.len() - data.len() % N..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_test() {
+        let data: Vec<u8> = (0..150).map(|i| i as u8).collect();
+        let whole: Vec<&[u8; 64]> = blocks(&data).collect();
+        assert_eq!(whole.len(), 2);
+        assert_eq!(whole[1][0], 64);
+        assert_eq!(remainder::<64>(&data), &data[128..]);
+        assert!(remainder::<128>(&data[..128]).is_empty());
+    }
+}
@@ -0,0 +1,15 @@
+/// Compare two byte slices without stopping at the first difference, so the time taken
+/// doesn't give away how much of a secret value (like a MAC) was guessed correctly.
+/// Slices of different lengths are never equal, the length itself isn't treated as secret
+pub(crate) fn eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b) {
+        diff |= x ^ y;
+    }
+    // black_box stops the compiler from turning the loop back into an early exit
+    std::hint::black_box(diff) == 0
+}
@@ -0,0 +1,386 @@
+use crate::error::ShaError;
+
+/// What the streaming hashers have in common, for code that works with any of the algorithms
+/// (HMAC-style constructions, or hashing the same data with a configurable algorithm).
+/// The digest comes out as a fixed size array so generic code doesn't need to allocate
+/// ```
+/// use sha::digest::Digest;
+/// use sha::sha256::Sha256;
+///
+/// fn checksum<D: Digest>(data: &[u8]) -> D::Output {
+///     let mut hasher = D::new();
+///     hasher.update(data);
+///     hasher.finalize_bytes()
+/// }
+///
+/// let digest: [u8; 32] = checksum::<Sha256>(b"hello world");
+/// ```
+pub trait Digest: Clone {
+    /// Size of a message block in bytes
+    const BLOCK_SIZE: usize;
+
+    /// Size of the digest in bytes
+    const OUTPUT_SIZE: usize;
+
+    /// The digest bytes, always `[u8; OUTPUT_SIZE]`
+    type Output: AsRef<[u8]> + Copy;
+
+    /// Create a new hasher starting from the initial hash values
+    fn new() -> Self;
+
+    /// Add more of the message
+    fn update(&mut self, data: &[u8]);
+
+    /// Finish the hash and return the standard digest bytes
+    fn finalize_bytes(self) -> Self::Output;
+}
+
+/// Object safe version of `Digest` for picking the algorithm at runtime, see `algorithm::boxed_hasher`
+/// The digest comes back as a `Vec` since its length depends on the algorithm
+pub trait DynDigest {
+    /// Add more of the message
+    fn update(&mut self, data: &[u8]);
+
+    /// Finish the hash and return the digest bytes
+    fn finalize_boxed(self: Box<Self>) -> Vec<u8>;
+}
+
+impl<D: Digest> DynDigest for D {
+    fn update(&mut self, data: &[u8]) {
+        Digest::update(self, data);
+    }
+
+    fn finalize_boxed(self: Box<Self>) -> Vec<u8> {
+        self.finalize_bytes().as_ref().to_vec()
+    }
+}
+
+/// Lowercase hex of `bytes`, two characters per byte
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Write `digest` as lowercase hex into any `fmt::Write` sink, for formatting into a fixed buffer without a `String`
+pub fn write_hex(digest: &[u8], out: &mut impl core::fmt::Write) -> core::fmt::Result {
+    for byte in digest {
+        write!(out, "{:02x}", byte)?;
+    }
+    Ok(())
+}
+
+/// Uppercase hex bytes joined by colons, like `AB:CD:EF`, the way certificate fingerprints are shown
+/// (`openssl x509 -fingerprint` for example)
+pub fn to_hex_colon(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":")
+}
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// RFC 4648 base32 of `digest`, `padding` adds `=` up to a multiple of 8 characters
+/// Every 5 bytes make 8 characters, so a 32-byte digest is 52 characters plus 4 `=`
+pub fn to_base32(digest: &[u8], padding: bool) -> String {
+    let mut out = String::with_capacity(digest.len().div_ceil(5) * 8);
+    for chunk in digest.chunks(5) {
+        let mut group = [0u8; 5];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits = u64::from_be_bytes([0, 0, 0, group[0], group[1], group[2], group[3], group[4]]);
+
+        // only the characters that carry bits of the chunk, a partial chunk's last one is zero filled
+        let chars = (chunk.len() * 8).div_ceil(5);
+        for i in 0..chars {
+            out.push(BASE32_ALPHABET[(bits >> (35 - i * 5)) as usize & 0x1f] as char);
+        }
+        if padding {
+            out.extend(std::iter::repeat_n('=', 8 - chars));
+        }
+    }
+    out
+}
+
+/// Decode RFC 4648 base32, padded or not, upper or lower case
+/// Lengths that no byte string encodes to and leftover bits that aren't zero are rejected, so every input has
+/// one decoding
+pub fn from_base32(s: &str) -> Result<Vec<u8>, ShaError> {
+    let unpadded = s.trim_end_matches('=');
+    if unpadded.len() != s.len() && !s.len().is_multiple_of(8) {
+        return Err(ShaError::InvalidBase32);
+    }
+    // 2, 4, 5 and 7 characters end a partial group of 1 to 4 bytes, 1, 3 and 6 can't happen
+    if matches!(unpadded.len() % 8, 1 | 3 | 6) || s.len() - unpadded.len() >= 8 {
+        return Err(ShaError::InvalidBase32);
+    }
+
+    let mut out = Vec::with_capacity(unpadded.len() * 5 / 8);
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+    for c in unpadded.bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_uppercase())
+            .ok_or(ShaError::InvalidBase32)?;
+        bits = (bits << 5) | value as u32;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            out.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+    if bits != 0 {
+        return Err(ShaError::InvalidBase32);
+    }
+    Ok(out)
+}
+
+/// Parse a hex string into `out`, the string has to be exactly two characters per byte
+pub(crate) fn decode_hex(s: &str, out: &mut [u8]) -> Result<(), ShaError> {
+    if s.len() != out.len() * 2 {
+        return Err(ShaError::InvalidHex);
+    }
+
+    // nibble by nibble, `from_str_radix` would let a `+` sign through
+    fn nibble(c: u8) -> Result<u8, ShaError> {
+        match c {
+            b'0'..=b'9' => Ok(c - b'0'),
+            b'a'..=b'f' => Ok(c - b'a' + 10),
+            b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err(ShaError::InvalidHex),
+        }
+    }
+
+    for (byte, pair) in out.iter_mut().zip(s.as_bytes().chunks(2)) {
+        *byte = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+    Ok(())
+}
+
+/// Define a digest newtype around `[u8; $len]`, displayed and parsed as lowercase hex
+/// All the digest types share this so they behave the same way
+macro_rules! digest_type {
+    ($(#[$meta:meta])* $name:ident, $len:expr) => {
+        $(#[$meta])*
+        ///
+        /// Ordering is byte by byte, so digests sort the same way as their hex strings.
+        /// The `Hash` impl is only there so digests can be `HashMap` keys, it has nothing to do with SHA
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(pub [u8; $len]);
+
+        impl $name {
+            /// The digest bytes
+            pub fn as_bytes(&self) -> &[u8; $len] {
+                &self.0
+            }
+
+            /// Unwrap into the digest bytes
+            pub fn into_bytes(self) -> [u8; $len] {
+                self.0
+            }
+
+            /// Iterate over the digest bytes
+            pub fn iter(&self) -> std::slice::Iter<'_, u8> {
+                self.0.iter()
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            fn from(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl From<$name> for [u8; $len] {
+            fn from(digest: $name) -> Self {
+                digest.0
+            }
+        }
+
+        /// Compare against raw bytes, handy in tests. Not constant time, use `finalize_verify` or
+        /// `verify_hmac_sha256` for anything secret
+        impl PartialEq<[u8; $len]> for $name {
+            fn eq(&self, other: &[u8; $len]) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<$name> for [u8; $len] {
+            fn eq(&self, other: &$name) -> bool {
+                *self == other.0
+            }
+        }
+
+        impl IntoIterator for $name {
+            type Item = u8;
+            type IntoIter = std::array::IntoIter<u8, $len>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.into_iter()
+            }
+        }
+
+        impl<'a> IntoIterator for &'a $name {
+            type Item = &'a u8;
+            type IntoIter = std::slice::Iter<'a, u8>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter()
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                for byte in self.0.iter() {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = $crate::error::ShaError;
+
+            /// Parse a digest from hex, upper or lower case
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let mut bytes = [0u8; $len];
+                $crate::digest::decode_hex(s, &mut bytes)?;
+                Ok(Self(bytes))
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = $crate::error::ShaError;
+
+            /// Take a digest from raw bytes, the slice has to be exactly the digest length
+            fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+                bytes.try_into().map(Self).map_err(|_| $crate::error::ShaError::InvalidLength)
+            }
+        }
+
+        #[cfg(feature = "hex")]
+        impl $name {
+            /// Parse a digest from hex using the `hex` crate, upper or lower case
+            pub fn from_hex(s: &str) -> Result<Self, $crate::error::ShaError> {
+                let mut bytes = [0u8; $len];
+                hex::decode_to_slice(s, &mut bytes).map_err(|_| $crate::error::ShaError::InvalidHex)?;
+                Ok(Self(bytes))
+            }
+
+            /// The digest as a lowercase hex string
+            pub fn to_hex_string(&self) -> String {
+                hex::encode(self.0)
+            }
+        }
+    };
+}
+
+pub(crate) use digest_type;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha256::{self, Sha256};
+    use crate::sha512::{self, Sha512};
+
+    fn digest_of<D: Digest>(message: &[u8]) -> D::Output {
+        let mut hasher = D::new();
+        hasher.update(message);
+        hasher.finalize_bytes()
+    }
+
+    #[test]
+    fn generic_output_test() {
+        let sha256: [u8; 32] = digest_of::<Sha256>(b"hello world");
+        let sha512: [u8; 64] = digest_of::<Sha512>(b"hello world");
+
+        assert_eq!(sha256, sha256::hash_bytes(b"hello world"));
+        assert_eq!(sha512, sha512::hash_bytes(b"hello world"));
+        assert_eq!(sha256.as_ref().len(), <Sha256 as Digest>::OUTPUT_SIZE);
+        assert_eq!(sha512.as_ref().len(), <Sha512 as Digest>::OUTPUT_SIZE);
+    }
+
+    #[test]
+    fn decode_hex_sign_test() {
+        let mut out = [0u8; 20];
+        assert!(matches!(decode_hex(&"+a".repeat(20), &mut out), Err(ShaError::InvalidHex)));
+        assert!(matches!("+a".repeat(32).parse::<sha256::Sha256Digest>(), Err(ShaError::InvalidHex)));
+        assert!(matches!(decode_hex(&"-1".repeat(20), &mut out), Err(ShaError::InvalidHex)));
+        decode_hex(&"aF".repeat(20), &mut out).unwrap();
+        assert_eq!(out, [0xaf; 20]);
+    }
+
+    #[test]
+    fn to_hex_colon_test() {
+        assert_eq!(
+            to_hex_colon(&sha256::hash_bytes(b"abc")),
+            "BA:78:16:BF:8F:01:CF:EA:41:41:40:DE:5D:AE:22:23:B0:03:61:A3:96:17:7A:9C:B4:10:FF:61:F2:00:15:AD"
+        );
+        assert_eq!(to_hex_colon(&[0x0a]), "0A");
+        assert_eq!(to_hex_colon(&[]), "");
+    }
+
+    #[test]
+    fn base32_test() {
+        let digest = sha256::hash_bytes(b"abc");
+        let padded = to_base32(&digest, true);
+        assert_eq!(padded, "XJ4BNP4PAHH6UQKBIDPF3LRCEOYAGYNDSYLXVHFUCD7WD4QACWWQ====");
+        assert_eq!(to_base32(&digest, false), padded.trim_end_matches('='));
+        assert_eq!(from_base32(&padded).unwrap(), digest);
+        assert_eq!(from_base32(padded.trim_end_matches('=')).unwrap(), digest);
+        assert_eq!(from_base32(&padded.to_lowercase()).unwrap(), digest);
+
+        // RFC 4648 section 10
+        let rfc = [
+            ("", ""),
+            ("f", "MY======"),
+            ("fo", "MZXQ===="),
+            ("foo", "MZXW6==="),
+            ("foob", "MZXW6YQ="),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI======"),
+        ];
+        for (plain, encoded) in rfc {
+            assert_eq!(to_base32(plain.as_bytes(), true), encoded);
+            assert_eq!(from_base32(encoded).unwrap(), plain.as_bytes());
+        }
+
+        for bad in ["MZXW6YT", "MZXW6YR", "MZ=", "MY======MY", "MZ======", "M1======", "========"] {
+            assert!(matches!(from_base32(bad), Err(ShaError::InvalidBase32)), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn write_hex_test() {
+        // stack buffer that fails once it's full, like the heapless ones
+        struct StackBuf {
+            bytes: [u8; 64],
+            len: usize,
+        }
+
+        impl core::fmt::Write for StackBuf {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                let end = self.len + s.len();
+                if end > self.bytes.len() {
+                    return Err(core::fmt::Error);
+                }
+                self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+                self.len = end;
+                Ok(())
+            }
+        }
+
+        let digest = sha256::hash_bytes(b"abc");
+        let mut buf = StackBuf { bytes: [0; 64], len: 0 };
+        write_hex(&digest, &mut buf).unwrap();
+        assert_eq!(&buf.bytes[..buf.len], encode_hex(&digest).as_bytes());
+        assert!(write_hex(&[0], &mut buf).is_err());
+
+        let mut string = String::new();
+        write_hex(&[0x0a, 0xff], &mut string).unwrap();
+        assert_eq!(string, "0aff");
+    }
+}
@@ -0,0 +1,105 @@
+use crate::algorithm::Algorithm;
+use std::fmt;
+
+/// Errors from the fallible parts of the crate, so they all compose with `?`
+/// MAC and self test failures have their own types (`MacError`, `SelfTestError`), they shouldn't get mixed up with these
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ShaError {
+    /// The message is longer than the algorithm's length field can describe
+    MessageTooLong,
+    /// A string isn't valid hex, or is the wrong length for the digest it's parsed into
+    InvalidHex,
+    /// A string isn't valid RFC 4648 base32
+    InvalidBase32,
+    /// A slice or requested output doesn't have the length an operation needs
+    InvalidLength,
+    /// An algorithm name isn't one this crate implements
+    UnknownAlgorithm,
+    /// Reading or writing the data failed
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+}
+
+impl fmt::Display for ShaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaError::MessageTooLong => write!(f, "message too long"),
+            ShaError::InvalidHex => write!(f, "invalid hex digest"),
+            ShaError::InvalidBase32 => write!(f, "invalid base32"),
+            ShaError::InvalidLength => write!(f, "invalid length"),
+            ShaError::UnknownAlgorithm => write!(f, "unknown hash algorithm"),
+            #[cfg(feature = "std")]
+            ShaError::Io(err) => write!(f, "I/O error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ShaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            ShaError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for ShaError {
+    fn from(err: std::io::Error) -> Self {
+        ShaError::Io(err)
+    }
+}
+
+/// Returned when a MAC doesn't match, it deliberately doesn't say anything about how close it was
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MacError;
+
+impl fmt::Display for MacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MAC verification failed")
+    }
+}
+
+impl std::error::Error for MacError {}
+
+/// Returned by `self_test` when an algorithm doesn't produce its known answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfTestError {
+    /// The algorithm that failed
+    pub algorithm: Algorithm,
+}
+
+impl fmt::Display for SelfTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "self test failed for {:?}", self.algorithm)
+    }
+}
+
+impl std::error::Error for SelfTestError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_test() {
+        assert_eq!(ShaError::MessageTooLong.to_string(), "message too long");
+        assert_eq!(ShaError::InvalidHex.to_string(), "invalid hex digest");
+        assert_eq!(ShaError::InvalidBase32.to_string(), "invalid base32");
+        assert_eq!(ShaError::InvalidLength.to_string(), "invalid length");
+        assert_eq!(ShaError::UnknownAlgorithm.to_string(), "unknown hash algorithm");
+        assert_eq!(MacError.to_string(), "MAC verification failed");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_error_test() {
+        use std::error::Error;
+
+        let err = ShaError::from(std::io::Error::new(std::io::ErrorKind::NotFound, "no such file"));
+        assert_eq!(err.to_string(), "I/O error: no such file");
+        assert!(err.source().is_some());
+    }
+}
@@ -0,0 +1,206 @@
+use crate::digest::Digest;
+use crate::sha256::Sha256;
+
+/// Types that know how to feed themselves into a hasher, so structured data can be hashed without
+/// serializing it by hand first.
+///
+/// The encoding is meant to be unambiguous: integers are fixed size big-endian, and anything variable
+/// length (strings, slices, `Vec`s) starts with its length as an 8 byte big-endian number.
+/// So `("ab", "c")` and `("a", "bc")` hash differently, which plain concatenation wouldn't do
+/// ```
+/// use sha::hashable::{sha256_of, Hashable};
+/// use sha::digest::Digest;
+///
+/// struct User {
+///     name: String,
+///     id: u32,
+/// }
+///
+/// impl Hashable for User {
+///     fn hash_into<D: Digest>(&self, hasher: &mut D) {
+///         self.name.hash_into(hasher);
+///         self.id.hash_into(hasher);
+///     }
+/// }
+///
+/// let user = User { name: "alice".to_string(), id: 7 };
+/// assert_eq!(sha256_of(&user), sha256_of(&("alice", 7u32)));
+/// ```
+pub trait Hashable {
+    /// Feed the encoding of `self` into `hasher`
+    fn hash_into<D: Digest>(&self, hasher: &mut D);
+}
+
+/// Define a struct along with a `Hashable` impl that hashes its fields one after another, in the order they're declared
+/// Reordering the fields changes the hash, renaming them doesn't. Generic structs aren't supported
+/// ```
+/// use sha::hashable::sha256_of;
+///
+/// sha::hashable_struct! {
+///     #[derive(Debug)]
+///     pub struct Config {
+///         pub name: String,
+///         pub port: u16,
+///     }
+/// }
+///
+/// let config = Config { name: "server".to_string(), port: 8080 };
+/// assert_eq!(sha256_of(&config), sha256_of(&("server", 8080u16)));
+/// ```
+#[macro_export]
+macro_rules! hashable_struct {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty),*
+        }
+
+        impl $crate::hashable::Hashable for $name {
+            fn hash_into<D: $crate::digest::Digest>(&self, hasher: &mut D) {
+                $($crate::hashable::Hashable::hash_into(&self.$field, hasher);)*
+            }
+        }
+    };
+}
+
+/// SHA-256 digest of the `Hashable` encoding of `value`
+pub fn sha256_of<T: Hashable + ?Sized>(value: &T) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    value.hash_into(&mut hasher);
+    hasher.finalize_bytes()
+}
+
+fn hash_len<D: Digest>(len: usize, hasher: &mut D) {
+    hasher.update(&(len as u64).to_be_bytes());
+}
+
+macro_rules! hashable_int {
+    ($($int:ty),*) => {
+        $(
+            impl Hashable for $int {
+                fn hash_into<D: Digest>(&self, hasher: &mut D) {
+                    hasher.update(&self.to_be_bytes());
+                }
+            }
+        )*
+    };
+}
+
+hashable_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl Hashable for bool {
+    fn hash_into<D: Digest>(&self, hasher: &mut D) {
+        hasher.update(&[*self as u8]);
+    }
+}
+
+impl Hashable for str {
+    fn hash_into<D: Digest>(&self, hasher: &mut D) {
+        hash_len(self.len(), hasher);
+        hasher.update(self.as_bytes());
+    }
+}
+
+impl Hashable for String {
+    fn hash_into<D: Digest>(&self, hasher: &mut D) {
+        self.as_str().hash_into(hasher);
+    }
+}
+
+/// Length, then each element. For `[u8]` that's the same bytes as for a `str`
+impl<T: Hashable> Hashable for [T] {
+    fn hash_into<D: Digest>(&self, hasher: &mut D) {
+        hash_len(self.len(), hasher);
+        for item in self {
+            item.hash_into(hasher);
+        }
+    }
+}
+
+impl<T: Hashable, const N: usize> Hashable for [T; N] {
+    fn hash_into<D: Digest>(&self, hasher: &mut D) {
+        self[..].hash_into(hasher);
+    }
+}
+
+impl<T: Hashable> Hashable for Vec<T> {
+    fn hash_into<D: Digest>(&self, hasher: &mut D) {
+        self[..].hash_into(hasher);
+    }
+}
+
+impl<T: Hashable + ?Sized> Hashable for &T {
+    fn hash_into<D: Digest>(&self, hasher: &mut D) {
+        (**self).hash_into(hasher);
+    }
+}
+
+macro_rules! hashable_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Hashable),+> Hashable for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn hash_into<D: Digest>(&self, hasher: &mut D) {
+                let ($($name,)+) = self;
+                $($name.hash_into(hasher);)+
+            }
+        }
+    };
+}
+
+hashable_tuple!(T1);
+hashable_tuple!(T1, T2);
+hashable_tuple!(T1, T2, T3);
+hashable_tuple!(T1, T2, T3, T4);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha256::hash_bytes;
+
+    #[test]
+    fn encoding_test() {
+        assert_eq!(sha256_of(&0x01020304u32), hash_bytes(&[1, 2, 3, 4]));
+        assert_eq!(sha256_of("abc"), hash_bytes(b"\0\0\0\0\0\0\0\x03abc"));
+        assert_eq!(sha256_of(&b"abc"[..]), sha256_of("abc"));
+    }
+
+    #[test]
+    fn distinct_structures_test() {
+        assert_ne!(sha256_of(&("ab", "c")), sha256_of(&("a", "bc")));
+        assert_ne!(sha256_of(&vec![vec![1u8], vec![2]]), sha256_of(&vec![vec![1u8, 2]]));
+        assert_ne!(sha256_of(&1u32), sha256_of(&1u64));
+        assert_ne!(sha256_of(&(1u8, true)), sha256_of(&(1u8, false)));
+        assert_eq!(sha256_of(&("ab", 1u16)), sha256_of(&(String::from("ab"), 1u16)));
+    }
+
+    crate::hashable_struct! {
+        struct Pair {
+            label: String,
+            count: u32,
+        }
+    }
+
+    crate::hashable_struct! {
+        struct Swapped {
+            count: u32,
+            label: String,
+        }
+    }
+
+    #[test]
+    fn hashable_struct_test() {
+        let pair = Pair { label: "abc".to_string(), count: 3 };
+        let mut manual = Sha256::new();
+        "abc".hash_into(&mut manual);
+        3u32.hash_into(&mut manual);
+        assert_eq!(sha256_of(&pair), manual.finalize_bytes());
+
+        let swapped = Swapped { count: 3, label: "abc".to_string() };
+        assert_ne!(sha256_of(&pair), sha256_of(&swapped));
+    }
+}
@@ -0,0 +1,79 @@
+// HKDF expand step as per https://datatracker.ietf.org/doc/html/rfc5869, using HMAC-SHA256
+use crate::error::ShaError;
+use crate::hmac::HmacSha256;
+use crate::sha256::OUTPUT_SIZE;
+
+/// The block counter is a single byte, so HKDF can only produce 255 blocks of output
+pub const MAX_OUTPUT_LEN: usize = 255 * OUTPUT_SIZE;
+
+/// Fill `out` with bytes derived from `seed`, using HKDF-Expand with HMAC-SHA256 (and an empty info string)
+/// Each block is `T(i) = HMAC(seed, T(i - 1) || i)`, and `out` gets as many bytes of `T(1) || T(2) || ...` as it can hold.
+/// `seed` should already be a strong key (HKDF calls it the PRK), this doesn't do the extract step.
+/// Fails if `out` is longer than `MAX_OUTPUT_LEN`
+pub fn expand(seed: &[u8], out: &mut [u8]) -> Result<(), ShaError> {
+    if out.len() > MAX_OUTPUT_LEN {
+        return Err(ShaError::InvalidLength);
+    }
+
+    // the key setup is the same for every block, so only do it once
+    let keyed = HmacSha256::new(seed);
+    let mut previous: Option<[u8; 32]> = None;
+
+    for (i, chunk) in out.chunks_mut(OUTPUT_SIZE).enumerate() {
+        let mut mac = keyed.clone();
+        if let Some(t) = &previous {
+            mac.update(t);
+        }
+        mac.update(&[(i + 1) as u8]);
+
+        let t = mac.finalize();
+        chunk.copy_from_slice(&t[..chunk.len()]);
+        previous = Some(t);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_test() {
+        // RFC 5869 test case 3 (no salt or info)
+        let prk = [
+            0x19, 0xef, 0x24, 0xa3, 0x2c, 0x71, 0x7b, 0x16, 0x7f, 0x33, 0xa9, 0x1d, 0x6f, 0x64, 0x8b, 0xdf,
+            0x96, 0x59, 0x67, 0x76, 0xaf, 0xdb, 0x63, 0x77, 0xac, 0x43, 0x4c, 0x1c, 0x29, 0x3c, 0xcb, 0x04
+        ];
+        let mut okm = [0u8; 42];
+        expand(&prk, &mut okm).unwrap();
+        assert_eq!(okm, [
+            0x8d, 0xa4, 0xe7, 0x75, 0xa5, 0x63, 0xc1, 0x8f, 0x71, 0x5f, 0x80, 0x2a, 0x06, 0x3c, 0x5a, 0x31,
+            0xb8, 0xa1, 0x1f, 0x5c, 0x5e, 0xe1, 0x87, 0x9e, 0xc3, 0x45, 0x4e, 0x5f, 0x3c, 0x73, 0x8d, 0x2d,
+            0x9d, 0x20, 0x13, 0x95, 0xfa, 0xa4, 0xb6, 0x1a, 0x96, 0xc8
+        ]);
+    }
+
+    #[test]
+    fn expand_deterministic_test() {
+        let mut short = [0u8; 100];
+        let mut long = [0u8; 1000];
+        expand(b"seed", &mut short).unwrap();
+        expand(b"seed", &mut long).unwrap();
+
+        let mut again = [0u8; 1000];
+        expand(b"seed", &mut again).unwrap();
+        assert_eq!(long, again);
+        // shorter output is just a prefix of longer output
+        assert_eq!(short, long[..100]);
+    }
+
+    #[test]
+    fn expand_too_long_test() {
+        let mut max = vec![0u8; MAX_OUTPUT_LEN];
+        assert!(expand(b"seed", &mut max).is_ok());
+
+        let mut too_long = vec![0u8; MAX_OUTPUT_LEN + 1];
+        assert!(matches!(expand(b"seed", &mut too_long), Err(ShaError::InvalidLength)));
+    }
+}
@@ -0,0 +1,172 @@
+// HMAC as per https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.198-1.pdf (also RFC 2104)
+use crate::ct;
+use crate::digest::Digest;
+use crate::error::MacError;
+use crate::sha1::{self, Sha1};
+use crate::sha256::{self, Sha256, BLOCK_SIZE};
+
+/// The inner and outer padded keys for `D`, which has to have a 64 byte block like SHA-1 and SHA-256 (checked at compile time)
+/// Keys longer than the block size are hashed first, then the key is padded with zeros to a full block
+fn key_pads<D: Digest>(key: &[u8]) -> ([u8; BLOCK_SIZE], [u8; BLOCK_SIZE]) {
+    const { assert!(D::BLOCK_SIZE == BLOCK_SIZE, "key_pads only handles hashes with a 64 byte block") };
+
+    let mut block_key = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        let mut hasher = D::new();
+        hasher.update(key);
+        let hashed = hasher.finalize_bytes();
+        block_key[..D::OUTPUT_SIZE].copy_from_slice(hashed.as_ref());
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner_pad = [0x36u8; BLOCK_SIZE];
+    let mut outer_pad = [0x5cu8; BLOCK_SIZE];
+    for i in 0..BLOCK_SIZE {
+        inner_pad[i] ^= block_key[i];
+        outer_pad[i] ^= block_key[i];
+    }
+    (inner_pad, outer_pad)
+}
+
+/// Incremental HMAC-SHA256, for when the message isn't available all at once
+/// ```
+/// use sha::hmac::{hmac_sha256, HmacSha256};
+///
+/// let mut mac = HmacSha256::new(b"key");
+/// mac.update(b"hello ");
+/// mac.update(b"world");
+/// assert_eq!(mac.finalize(), hmac_sha256(b"key", b"hello world"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct HmacSha256 {
+    inner: Sha256,
+    outer: Sha256,
+}
+
+impl HmacSha256 {
+    /// Set up the inner and outer hashers for `key`
+    /// Keys longer than the block size are hashed first, then the key is padded with zeros to a full block
+    pub fn new(key: &[u8]) -> Self {
+        let (inner_pad, outer_pad) = key_pads::<Sha256>(key);
+
+        let mut inner = Sha256::new();
+        inner.update(&inner_pad);
+        let mut outer = Sha256::new();
+        outer.update(&outer_pad);
+
+        Self { inner, outer }
+    }
+
+    /// Add more of the message
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Finish the inner hash and run it through the outer hash to get the MAC
+    pub fn finalize(self) -> [u8; 32] {
+        let mut outer = self.outer;
+        outer.update(&sha256::state_to_bytes(&self.inner.finalize()));
+        sha256::state_to_bytes(&outer.finalize())
+    }
+}
+
+/// HMAC-SHA256 of a whole message in one call
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac = HmacSha256::new(key);
+    mac.update(message);
+    mac.finalize()
+}
+
+/// HMAC-SHA1 of a whole message in one call, only here for older protocols like HOTP/TOTP that are built on it
+pub fn hmac_sha1(key: &[u8], message: &[u8]) -> [u8; 20] {
+    let (inner_pad, outer_pad) = key_pads::<Sha1>(key);
+
+    let mut inner = Sha1::new();
+    inner.update(&inner_pad);
+    inner.update(message);
+    let mut outer = Sha1::new();
+    outer.update(&outer_pad);
+    outer.update(&sha1::state_to_bytes(&inner.finalize()));
+    sha1::state_to_bytes(&outer.finalize())
+}
+
+/// Check that `tag` is the HMAC-SHA256 of `message` under `key`
+/// Use this instead of comparing with `==`, the comparison takes the same time no matter
+/// where the tags differ. A tag of the wrong length is just rejected.
+///
+/// The result is a `Result` so a failed check can't be silently ignored, dropping it is a warning
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// use sha::hmac::verify_hmac_sha256;
+///
+/// verify_hmac_sha256(b"key", b"message", &[0u8; 32]);
+/// ```
+pub fn verify_hmac_sha256(key: &[u8], message: &[u8], tag: &[u8]) -> Result<(), MacError> {
+    if ct::eq(&hmac_sha256(key, message), tag) {
+        Ok(())
+    } else {
+        Err(MacError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hmac_sha256_test() {
+        // RFC 4231 test case 2
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(mac, [
+            0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95, 0x75, 0xc7,
+            0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9, 0x64, 0xec, 0x38, 0x43
+        ]);
+    }
+
+    #[test]
+    fn hmac_sha256_long_key_test() {
+        // RFC 4231 test case 6, the key is longer than a block so it gets hashed first
+        let key = [0xaa; 131];
+        let mac = hmac_sha256(&key, b"Test Using Larger Than Block-Size Key - Hash Key First");
+        assert_eq!(mac, [
+            0x60, 0xe4, 0x31, 0x59, 0x1e, 0xe0, 0xb6, 0x7f, 0x0d, 0x8a, 0x26, 0xaa, 0xcb, 0xf5, 0xb7, 0x7f,
+            0x8e, 0x0b, 0xc6, 0x21, 0x37, 0x28, 0xc5, 0x14, 0x05, 0x46, 0x04, 0x0f, 0x0e, 0xe3, 0x7f, 0x54
+        ]);
+    }
+
+    #[test]
+    fn hmac_sha256_streaming_test() {
+        let message = b"a".repeat(1000);
+        let mut mac = HmacSha256::new(b"key");
+        for piece in message.chunks(13) {
+            mac.update(piece);
+        }
+        assert_eq!(mac.finalize(), hmac_sha256(b"key", &message));
+    }
+
+    #[test]
+    fn hmac_sha1_test() {
+        // RFC 2202 test cases 2 and 6
+        assert_eq!(hmac_sha1(b"Jefe", b"what do ya want for nothing?"), [
+            0xef, 0xfc, 0xdf, 0x6a, 0xe5, 0xeb, 0x2f, 0xa2, 0xd2, 0x74,
+            0x16, 0xd5, 0xf1, 0x84, 0xdf, 0x9c, 0x25, 0x9a, 0x7c, 0x79
+        ]);
+        assert_eq!(hmac_sha1(&[0xaa; 80], b"Test Using Larger Than Block-Size Key - Hash Key First"), [
+            0xaa, 0x4a, 0xe5, 0xe1, 0x52, 0x72, 0xd0, 0x0e, 0x95, 0x70,
+            0x56, 0x37, 0xce, 0x8a, 0x3b, 0x55, 0xed, 0x40, 0x21, 0x12
+        ]);
+    }
+
+    #[test]
+    fn verify_hmac_sha256_test() {
+        let tag = hmac_sha256(b"key", b"message");
+        assert_eq!(verify_hmac_sha256(b"key", b"message", &tag), Ok(()));
+
+        let mut flipped = tag;
+        flipped[31] ^= 0x01;
+        assert_eq!(verify_hmac_sha256(b"key", b"message", &flipped), Err(MacError));
+
+        assert_eq!(verify_hmac_sha256(b"key", b"message", &tag[..31]), Err(MacError));
+    }
+}
@@ -1,3 +1,17 @@
+pub mod algorithm;
+mod block;
+mod ct;
+pub mod digest;
+pub mod error;
+pub mod hashable;
+pub mod hkdf;
+pub mod hmac;
+pub mod otp;
+pub mod pkcs1;
+pub mod pow;
+pub mod selftest;
 pub mod sha1;
 pub mod sha256;
-pub mod sha512;
\ No newline at end of file
+pub mod sha512;
+#[cfg(feature = "wasm")]
+pub mod wasm;
\ No newline at end of file
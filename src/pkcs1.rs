@@ -0,0 +1,68 @@
+use crate::algorithm::Algorithm;
+use crate::error::ShaError;
+use crate::{sha1, sha256, sha512};
+
+/// EMSA-PKCS1-v1_5 encoding of a digest (RFC 8017 section 9.2), the block that gets signed in an RSA PKCS#1 v1.5 signature:
+/// `0x00 0x01 FF..FF 0x00 DigestInfo`, with the `0xFF` padding filling it out to `em_len` bytes.
+///
+/// `digest` has to be the right length for `algorithm`, and `em_len` (the RSA modulus size in bytes)
+/// has to leave room for at least 8 bytes of padding, otherwise it's `ShaError::InvalidLength`
+pub fn emsa_pkcs1_v15_encode(digest: &[u8], em_len: usize, algorithm: Algorithm) -> Result<Vec<u8>, ShaError> {
+    let output_size = match algorithm {
+        Algorithm::Sha1 => sha1::OUTPUT_SIZE,
+        Algorithm::Sha256 => sha256::OUTPUT_SIZE,
+        Algorithm::Sha512 => sha512::OUTPUT_SIZE,
+    };
+    if digest.len() != output_size {
+        return Err(ShaError::InvalidLength);
+    }
+
+    let digest_info = match algorithm {
+        Algorithm::Sha1 => sha1::digest_info(digest),
+        Algorithm::Sha256 => sha256::digest_info(digest),
+        Algorithm::Sha512 => sha512::digest_info(digest),
+    };
+    // 0x00 0x01, at least 8 bytes of 0xFF, then 0x00
+    if em_len < digest_info.len() + 11 {
+        return Err(ShaError::InvalidLength);
+    }
+
+    let mut encoded = Vec::with_capacity(em_len);
+    encoded.extend_from_slice(&[0x00, 0x01]);
+    encoded.resize(em_len - digest_info.len() - 1, 0xff);
+    encoded.push(0x00);
+    encoded.extend_from_slice(&digest_info);
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emsa_pkcs1_v15_encode_test() {
+        let digest = sha256::hash_bytes(b"abc");
+        // 19 byte DigestInfo prefix + 32 byte digest + 11 is the shortest allowed
+        let encoded = emsa_pkcs1_v15_encode(&digest, 62, Algorithm::Sha256).unwrap();
+
+        let mut expected = vec![0x00, 0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+        expected.extend_from_slice(&[
+            0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04, 0x20,
+        ]);
+        expected.extend_from_slice(&digest);
+        assert_eq!(encoded, expected);
+
+        // a 2048 bit modulus
+        let encoded = emsa_pkcs1_v15_encode(&digest, 256, Algorithm::Sha256).unwrap();
+        assert_eq!(encoded.len(), 256);
+        assert!(encoded[2..256 - 52].iter().all(|&b| b == 0xff));
+        assert_eq!(encoded[256 - 52], 0x00);
+    }
+
+    #[test]
+    fn emsa_pkcs1_v15_encode_errors_test() {
+        let digest = sha256::hash_bytes(b"abc");
+        assert!(matches!(emsa_pkcs1_v15_encode(&digest, 61, Algorithm::Sha256), Err(ShaError::InvalidLength)));
+        assert!(matches!(emsa_pkcs1_v15_encode(&digest, 256, Algorithm::Sha1), Err(ShaError::InvalidLength)));
+    }
+}
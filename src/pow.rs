@@ -0,0 +1,64 @@
+use crate::sha256::{state_to_bytes, Sha256};
+
+/// How many zero bits the digest starts with, counting from the most significant bit of the first byte
+pub fn leading_zero_bits(digest: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in digest {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+/// Whether the digest starts with at least `target_bits` zero bits, the usual proof of work check
+pub fn meets_difficulty(digest: &[u8], target_bits: u32) -> bool {
+    leading_zero_bits(digest) >= target_bits
+}
+
+/// Look for a nonce that makes `SHA256(prefix || nonce)` meet `target_bits`, with the nonce as 8 bytes big-endian
+/// Tries the nonces in `nonce_range` in order and returns the first one that works, or `None` if none do.
+/// The prefix is only absorbed once, each attempt starts from a copy of that state
+pub fn find_nonce(prefix: &[u8], target_bits: u32, nonce_range: std::ops::Range<u64>) -> Option<u64> {
+    let prefixed = Sha256::new_with_prefix(prefix);
+    nonce_range.into_iter().find(|nonce| {
+        let digest = prefixed.clone().chain(&nonce.to_be_bytes()).finalize();
+        meets_difficulty(&state_to_bytes(&digest), target_bits)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leading_zero_bits_test() {
+        assert_eq!(leading_zero_bits(&[0xff, 0x00]), 0);
+        assert_eq!(leading_zero_bits(&[0x00, 0x0f, 0xff]), 12);
+        assert_eq!(leading_zero_bits(&[0x00, 0x00, 0x80]), 16);
+        assert_eq!(leading_zero_bits(&[0x01]), 7);
+        assert_eq!(leading_zero_bits(&[0x00; 4]), 32);
+        assert_eq!(leading_zero_bits(&[]), 0);
+    }
+
+    #[test]
+    fn meets_difficulty_test() {
+        let digest = [0x00, 0x0f, 0xff];
+        assert!(meets_difficulty(&digest, 0));
+        assert!(meets_difficulty(&digest, 11));
+        assert!(meets_difficulty(&digest, 12));
+        assert!(!meets_difficulty(&digest, 13));
+    }
+
+    #[test]
+    fn find_nonce_test() {
+        let prefix = b"block header";
+        let nonce = find_nonce(prefix, 8, 0..100_000).unwrap();
+        let digest = crate::sha256::hash_bytes(&[&prefix[..], &nonce.to_be_bytes()].concat());
+        assert!(meets_difficulty(&digest, 8));
+        // it's the first one
+        assert_eq!(find_nonce(prefix, 8, 0..nonce), None);
+        assert_eq!(find_nonce(prefix, 8, 0..0), None);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::algorithm::{hash_with, Algorithm};
+use crate::error::SelfTestError;
+
+/// Known answers for "abc", from the FIPS 180 examples
+const KNOWN_ANSWERS: [(Algorithm, &str); 3] = [
+    (Algorithm::Sha1, "a9993e364706816aba3e25717850c26c9cd0d89d"),
+    (Algorithm::Sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+    (
+        Algorithm::Sha512,
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+         2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+    ),
+];
+
+/// Run every algorithm against a built-in known answer, for power-on style checks at startup
+/// Returns the first algorithm that gets it wrong
+/// ```
+/// sha::selftest::self_test().expect("hashing is broken");
+/// ```
+pub fn self_test() -> Result<(), SelfTestError> {
+    for (algorithm, expected) in KNOWN_ANSWERS {
+        let digest = hash_with(algorithm, b"abc");
+        if crate::digest::encode_hex(&digest) != expected {
+            return Err(SelfTestError { algorithm });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn self_test_test() {
+        assert_eq!(self_test(), Ok(()));
+    }
+
+    #[test]
+    fn self_test_error_names_algorithm_test() {
+        let err = SelfTestError { algorithm: Algorithm::Sha256 };
+        assert!(err.to_string().contains("Sha256"));
+    }
+}
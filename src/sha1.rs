@@ -1,16 +1,55 @@
 // SHA 1 - https://nvlpubs.nist.gov/nistpubs/Legacy/FIPS/fipspub180-1.pdf
+use crate::block::{blocks, remainder};
+use crate::digest::{digest_type, encode_hex, Digest};
+use crate::error::ShaError;
+
 /// K constants for SHA-1
 pub const K: [u32; 4] = [0x5A827999, 0x6ED9EBA1, 0x8F1BBCDC, 0xCA62C1D6];
 
+/// Per-round constants, K expanded so round t just indexes with t
+const ROUND_K: [u32; 80] = {
+    let mut table = [0u32; 80];
+    let mut t = 0;
+    while t < 80 {
+        table[t] = K[t / 20];
+        t += 1;
+    }
+    table
+};
+
 /// Initial hash values for SHA-1
 pub const H: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
 
+/// Size of a SHA-1 message block in bytes
+pub const BLOCK_SIZE: usize = 64;
+
+/// Size of a SHA-1 digest in bytes
+pub const OUTPUT_SIZE: usize = 20;
+
+/// DER encoding of the SHA-1 object identifier (1.3.14.3.2.26), tag and length included
+pub const OID: [u8; 7] = [0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a];
+
+/// What goes in front of the digest in a DER `DigestInfo` (RFC 8017 section 9.2 note 1):
+/// the algorithm identifier with NULL parameters, then the header of the digest octet string
+pub const DIGEST_INFO_PREFIX: [u8; 15] = [
+    0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+];
+
+/// How long `message_padding` makes a message of `message_len` bytes, without building it
+/// That's the message, the 0x80 byte and the 8 length bytes, rounded up to whole blocks
+pub fn padded_len(message_len: usize) -> usize {
+    (message_len + 9).div_ceil(BLOCK_SIZE) * BLOCK_SIZE
+}
+
 /// Following the standard, the message is to be padded as follows:
 /// 1. Append a 1 bit to the message
 /// 2. Append 0 bits until the length of the message is congruent to 448 mod 512
 /// 3. Append the length of the message in bits as a 64 bit number
+///
+/// A message that is exactly one block (64 bytes) becomes two blocks, and 128 bytes becomes three
 pub fn message_padding(message: &[u8]) -> Vec<u8> {
-    let message_len_bits = (message.len() * 8) as u64;
+    // widen before multiplying, a usize can overflow on 32 bit targets
+    let message_len_bits = message.len() as u64 * 8;
     let mut message_bytes = Vec::from(message);
 
     // append 1 bit as per the standard
@@ -18,7 +57,7 @@ pub fn message_padding(message: &[u8]) -> Vec<u8> {
  
     // message must be a multiple of 512 bits, so add padding to the message until it is
     let padding_len = (64 - (message_bytes.len() + 8) % 64) % 64;
-    message_bytes.extend(std::iter::repeat(0).take(padding_len));
+    message_bytes.extend(std::iter::repeat_n(0, padding_len));
 
     // now just append the length of the message (as stated in the standard)
     message_bytes.extend_from_slice(&message_len_bits.to_be_bytes());
@@ -32,6 +71,7 @@ pub fn message_padding(message: &[u8]) -> Vec<u8> {
 /// f(t;B,C,D) = (B AND C) OR (B AND D) OR (C AND D) when 40 ≤ t ≤ 59
 /// f(t;B,C,D) = B XOR C XOR D when 60 ≤ t ≤ 79
 /// This one will panic if the value of t is not in the range of 0 to 79
+#[inline]
 pub fn func_f(t: u32, b: u32, c: u32, d: u32) -> u32 {
     match t {
         0..=19 => (b & c) | ((!b) & d),
@@ -48,6 +88,7 @@ pub fn func_f(t: u32, b: u32, c: u32, d: u32) -> u32 {
 /// Kt = 0x8F1BBCDC when 40 ≤ t ≤ 59
 /// Kt = 0xCA62C1D6 when 60 ≤ t ≤ 79
 /// If it is not in the range of 0 to 79, it will panic
+#[inline]
 pub fn get_k(t: u32) -> u32 {
     match t {
         0..=19 => K[0],
@@ -60,6 +101,7 @@ pub fn get_k(t: u32) -> u32 {
 
 /// The main hashing function of the SHA-1 algorithm
 /// It expects a message as a byte slice and returns the hash as an array of 5 u32 values
+/// (use `hash_bytes` to get the standard 20 byte digest instead)
 /// ```rust
 /// use sha::sha1::hash;
 /// 
@@ -75,61 +117,411 @@ pub fn get_k(t: u32) -> u32 {
 /// 
 /// ```
 pub fn hash(message: &[u8]) -> [u32; 5] {
+    // short messages pad out to a single block, so skip building the padded copy
+    if message.len() < BLOCK_SIZE - 8 {
+        let mut h = H;
+        compress(&mut h, &single_block(message));
+        return h;
+    }
+
     let message_bytes = message_padding(message);
     let mut h = H;
 
     // this is all basically just the sha1 algorithm from the pdf file
-    for chunk in message_bytes.chunks(64) {
-        let chunk_len = chunk.len();
-        let mut padded_chunk = Vec::from(chunk);
-        if chunk_len < 64 {
-            padded_chunk.resize(64, 0); // pad with zeros so it doesn't panic, maybe im dumb
-        }
-
-        let mut w = [0u32; 80];
-        for t in 0..16 {
-            w[t] = u32::from_be_bytes([
-                padded_chunk[t * 4],
-                padded_chunk[t * 4 + 1],
-                padded_chunk[t * 4 + 2],
-                padded_chunk[t * 4 + 3],
-            ]);
-        }
-        for t in 16..80 {
-            w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
-        }
-        let mut a = h[0];
-        let mut b = h[1];
-        let mut c = h[2];
-        let mut d = h[3];
-        let mut e = h[4];
-        for t in 0..80 {
-            let temp = 
-                a.rotate_left(5)
-                .wrapping_add(func_f(t, b, c, d))
+    for chunk in blocks(&message_bytes) {
+        compress(&mut h, chunk);
+    }
+    h
+}
+
+/// The padded form of a message shorter than `BLOCK_SIZE - 8`, which fits in one block
+/// Same layout as `message_padding`, just built on the stack
+fn single_block(message: &[u8]) -> [u8; BLOCK_SIZE] {
+    let mut block = [0u8; BLOCK_SIZE];
+    block[..message.len()].copy_from_slice(message);
+    block[message.len()] = 0x80;
+    block[BLOCK_SIZE - 8..].copy_from_slice(&((message.len() as u64) * 8).to_be_bytes());
+    block
+}
+
+/// Process a single 64 byte chunk of the padded message, updating the hash values in place
+fn compress(h: &mut [u32; 5], chunk: &[u8; BLOCK_SIZE]) {
+    let mut w = [0u32; 80];
+    // the chunk is 16 big-endian words, the zip stops after those
+    for (w_t, word) in w.iter_mut().zip(chunk.chunks_exact(4)) {
+        *w_t = u32::from_be_bytes(word.try_into().unwrap());
+    }
+    for t in 16..80 {
+        w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+    }
+    let mut a = h[0];
+    let mut b = h[1];
+    let mut c = h[2];
+    let mut d = h[3];
+    let mut e = h[4];
+    // one loop per group of 20 rounds, each with its own f(t;B,C,D) from the standard written out in place,
+    // so nothing is picked per round and nothing goes through a call
+    macro_rules! round {
+        ($t:expr, $f:expr) => {
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add($f)
                 .wrapping_add(e)
-                .wrapping_add(w[t as usize])
-                .wrapping_add(get_k(t));
+                .wrapping_add(w[$t])
+                .wrapping_add(ROUND_K[$t]);
             e = d;
             d = c;
             c = b.rotate_left(30);
             b = a;
             a = temp;
+        };
+    }
+    for t in 0..20 {
+        round!(t, (b & c) | ((!b) & d));
+    }
+    for t in 20..40 {
+        round!(t, b ^ c ^ d);
+    }
+    for t in 40..60 {
+        round!(t, (b & c) | (b & d) | (c & d));
+    }
+    for t in 60..80 {
+        round!(t, b ^ c ^ d);
+    }
+
+    h[0] = h[0].wrapping_add(a);
+    h[1] = h[1].wrapping_add(b);
+    h[2] = h[2].wrapping_add(c);
+    h[3] = h[3].wrapping_add(d);
+    h[4] = h[4].wrapping_add(e);
+}
+
+/// Convert the hash values into the standard SHA-1 digest bytes (each word big-endian)
+pub fn state_to_bytes(state: &[u32; 5]) -> [u8; 20] {
+    let mut bytes = [0u8; 20];
+    for (chunk, h) in bytes.chunks_exact_mut(4).zip(state.iter()) {
+        chunk.copy_from_slice(&h.to_be_bytes());
+    }
+    bytes
+}
+
+/// Convert digest bytes back into the hash values, the opposite of `state_to_bytes`
+pub fn bytes_to_state(bytes: &[u8; 20]) -> [u32; 5] {
+    let mut state = [0u32; 5];
+    for (h, chunk) in state.iter_mut().zip(bytes.chunks_exact(4)) {
+        *h = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+    state
+}
+
+/// Same as `state_to_bytes` but with each word in little-endian order
+/// Big-endian is what the standard uses and what other tools print, so only use this
+/// for formats that store the words the other way around
+pub fn state_to_bytes_le(state: &[u32; 5]) -> [u8; 20] {
+    let mut bytes = [0u8; 20];
+    for (chunk, h) in bytes.chunks_exact_mut(4).zip(state.iter()) {
+        chunk.copy_from_slice(&h.to_le_bytes());
+    }
+    bytes
+}
+
+digest_type!(
+    /// A SHA-1 digest as its 20 standard (big-endian) bytes, the form used for Git object ids
+    Sha1Digest, 20
+);
+
+/// Hash a message and return the 20 digest bytes instead of the hash words
+pub fn hash_bytes(message: &[u8]) -> [u8; 20] {
+    state_to_bytes(&hash(message))
+}
+
+/// The DER `DigestInfo` for a SHA-1 digest, as signed in PKCS#1 v1.5 signatures
+/// Panics if `digest` isn't `OUTPUT_SIZE` bytes, the prefix encodes that length
+pub fn digest_info(digest: &[u8]) -> Vec<u8> {
+    assert_eq!(digest.len(), OUTPUT_SIZE, "not a SHA-1 digest");
+    [&DIGEST_INFO_PREFIX[..], digest].concat()
+}
+
+/// Streaming SHA-1 hasher, for when the message isn't available all at once
+/// Feeding the message in pieces gives the same result as `hash` on the whole message.
+/// All of its state is in fixed size arrays, so `update` and `finalize` never allocate
+/// ```
+/// use sha::sha1::{hash, Sha1};
+///
+/// let mut hasher = Sha1::new();
+/// hasher.update(b"hello ");
+/// hasher.update(b"world");
+/// assert_eq!(hasher.finalize(), hash(b"hello world"));
+/// ```
+///
+/// `finalize` takes the hasher by value, `finalize_reset` leaves a fresh hasher and `digest` works on a copy,
+/// so there's never a finished hasher around to be misused
+#[derive(Clone, Debug)]
+pub struct Sha1 {
+    h: [u32; 5],
+    buffer: [u8; BLOCK_SIZE],
+    buffer_len: usize,
+    length: u64,
+}
+
+impl Sha1 {
+    /// Create a new hasher starting from the initial hash values
+    pub fn new() -> Self {
+        Self {
+            h: H,
+            buffer: [0; BLOCK_SIZE],
+            buffer_len: 0,
+            length: 0,
+        }
+    }
+
+    /// Add more of the message, anything that doesn't fill a whole block is kept until the next call
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        // top up a partly filled buffer first
+        if self.buffer_len > 0 {
+            let take = (BLOCK_SIZE - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+
+            if self.buffer_len < BLOCK_SIZE {
+                return;
+            }
+            compress(&mut self.h, &self.buffer);
+            self.buffer_len = 0;
+        }
+
+        // whole blocks can be compressed straight from the input, no need to copy them into the buffer
+        for block in blocks(data) {
+            compress(&mut self.h, block);
         }
 
-        h[0] = h[0].wrapping_add(a);
-        h[1] = h[1].wrapping_add(b);
-        h[2] = h[2].wrapping_add(c);
-        h[3] = h[3].wrapping_add(d);
-        h[4] = h[4].wrapping_add(e);
+        let rest = remainder::<BLOCK_SIZE>(data);
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
     }
-    h
+
+    /// A hasher that has already absorbed `prefix`, for protocols that hash a fixed prefix before the data
+    /// To skip re-hashing a long prefix every time, make this once and `clone` it per message
+    pub fn new_with_prefix(prefix: &[u8]) -> Self {
+        let mut hasher = Self::new();
+        hasher.update(prefix);
+        hasher
+    }
+
+    /// `update` that takes and returns the hasher, for writing it all in one expression
+    /// ```
+    /// use sha::sha1::{hash, Sha1};
+    ///
+    /// assert_eq!(Sha1::new().chain(b"a").chain(b"b").finalize(), hash(b"ab"));
+    /// ```
+    pub fn chain(mut self, data: &[u8]) -> Self {
+        self.update(data);
+        self
+    }
+
+    /// Add a string to the message, this hashes its UTF-8 encoding, same as `update(s.as_bytes())`
+    pub fn update_str(&mut self, s: &str) {
+        self.update(s.as_bytes());
+    }
+
+    /// Add `data` as one field: its length as 8 bytes big-endian, then the bytes
+    /// Fields written this way can't run into each other, `("ab", "c")` and `("a", "bc")` hash differently
+    pub fn update_len_prefixed(&mut self, data: &[u8]) {
+        self.update(&(data.len() as u64).to_be_bytes());
+        self.update(data);
+    }
+
+    /// Pad whatever is left in the buffer (same as `message_padding`) and return the hash
+    pub fn finalize(mut self) -> [u32; 5] {
+        let message_len_bits = self.length.wrapping_mul(8);
+
+        self.buffer[self.buffer_len] = 0x80;
+        self.buffer[self.buffer_len + 1..].fill(0);
+
+        // not enough room left for the length, so it goes in an extra block
+        if self.buffer_len >= BLOCK_SIZE - 8 {
+            compress(&mut self.h, &self.buffer);
+            self.buffer = [0; BLOCK_SIZE];
+        }
+
+        self.buffer[BLOCK_SIZE - 8..].copy_from_slice(&message_len_bits.to_be_bytes());
+        compress(&mut self.h, &self.buffer);
+
+        self.h
+    }
+
+    /// Finish the hash and return the digest bytes along with the total number of bytes hashed
+    pub fn finalize_with_len(self) -> ([u8; 20], u64) {
+        let len = self.length;
+        (state_to_bytes(&self.finalize()), len)
+    }
+
+    /// The current hash values, buffer fill level and length as readable text, for debugging and for
+    /// following the algorithm along by hand, like `h0=67452301 ... buffer=3/64 length=3`
+    #[cfg(feature = "debug-dump")]
+    pub fn debug_state(&self) -> String {
+        let mut dump = String::new();
+        for (i, word) in self.h.iter().enumerate() {
+            dump.push_str(&format!("h{}={:08x} ", i, word));
+        }
+        dump.push_str(&format!("buffer={}/{} length={}", self.buffer_len, BLOCK_SIZE, self.length));
+        dump
+    }
+
+    /// How many bytes have been added so far, across all the `update` calls
+    pub fn bytes_processed(&self) -> u64 {
+        self.length
+    }
+
+    /// The hash of everything added so far, without finishing the hasher
+    /// The padding is done on a copy, so more data can still be added afterwards
+    pub fn digest(&self) -> [u32; 5] {
+        self.clone().finalize()
+    }
+
+    /// Finish the hash and reset the hasher back to the initial hash values, ready for the next message
+    pub fn finalize_reset(&mut self) -> [u32; 5] {
+        std::mem::take(self).finalize()
+    }
+
+    /// Finish the hash and write the digest bytes (big-endian, same as `state_to_bytes`) into `out`
+    /// Fails if `out` is shorter than `OUTPUT_SIZE`, any bytes after the digest are left alone
+    pub fn finalize_into(self, out: &mut [u8]) -> Result<(), ShaError> {
+        if out.len() < OUTPUT_SIZE {
+            return Err(ShaError::InvalidLength);
+        }
+        out[..OUTPUT_SIZE].copy_from_slice(&state_to_bytes(&self.finalize()));
+        Ok(())
+    }
+
+    /// Finish the hash and check it against `expected` digest bytes, in constant time
+    /// A wrong length is just a mismatch
+    pub fn finalize_verify(self, expected: &[u8]) -> bool {
+        crate::ct::eq(&state_to_bytes(&self.finalize()), expected)
+    }
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Digest for Sha1 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+    const OUTPUT_SIZE: usize = OUTPUT_SIZE;
+    type Output = [u8; 20];
+
+    fn new() -> Self {
+        Sha1::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha1::update(self, data);
+    }
+
+    fn finalize_bytes(self) -> Self::Output {
+        state_to_bytes(&self.finalize())
+    }
+}
+
+/// Hash a message made up of several parts without joining them together first
+/// The result is the same as hashing the concatenation of `parts`
+pub fn hash_vectored(parts: &[&[u8]]) -> [u32; 5] {
+    let mut hasher = Sha1::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize()
+}
+
+/// The id Git gives a file's contents: the SHA-1 of `"blob <length>\0"` followed by the content
+/// Works the same for binary and empty content
+/// ```
+/// use sha::sha1::{git_blob_oid, Sha1Digest};
+///
+/// // same as `git hash-object` on an empty file
+/// let oid = Sha1Digest(git_blob_oid(b""));
+/// assert_eq!(oid.to_string(), "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+/// ```
+pub fn git_blob_oid(content: &[u8]) -> [u8; 20] {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", content.len()).as_bytes());
+    hasher.update(content);
+    state_to_bytes(&hasher.finalize())
+}
+
+/// The first `N` bytes of the digest, for short ids and display hashes
+/// `N` can be anything from 0 up to `OUTPUT_SIZE`, this is checked at compile time.
+/// Keep in mind a truncated hash is only as collision resistant as its length
+pub fn truncated<const N: usize>(message: &[u8]) -> [u8; N] {
+    const { assert!(N <= OUTPUT_SIZE, "can't truncate a digest to more than OUTPUT_SIZE bytes") };
+
+    let mut out = [0u8; N];
+    out.copy_from_slice(&hash_bytes(message)[..N]);
+    out
+}
+
+/// Hash bytes from an iterator (a decompressor or some other generator) without collecting them first
+/// The bytes are gathered a block at a time before being passed to the hasher
+pub fn hash_iter<I: IntoIterator<Item = u8>>(iter: I) -> [u32; 5] {
+    let mut hasher = Sha1::new();
+    let mut block = [0u8; BLOCK_SIZE];
+    let mut block_len = 0;
+
+    for byte in iter {
+        block[block_len] = byte;
+        block_len += 1;
+        if block_len == BLOCK_SIZE {
+            hasher.update(&block);
+            block_len = 0;
+        }
+    }
+
+    hasher.update(&block[..block_len]);
+    hasher.finalize()
+}
+
+/// Hash each message separately, the results are in the same order as `messages`
+pub fn hash_all(messages: &[&[u8]]) -> Vec<[u32; 5]> {
+    messages.iter().map(|message| hash(message)).collect()
+}
+
+/// Format a digest the way `openssl dgst -sha1` prints it: `SHA1(<label>)= <hex>`
+/// `label` is whatever was hashed, usually a file name or `stdin`.
+/// This is the OpenSSL 1.x style, which is what most existing scripts parse
+pub fn openssl_format(label: &str, digest: &[u8]) -> String {
+    format!("SHA1({})= {}", label, encode_hex(digest))
+}
+
+/// Hash a string slice with SHA-1
+/// Note that this hashes the UTF-8 encoding of the string, which is what
+/// other implementations do when given text, so the result is the same as `hash(s.as_bytes())`
+pub fn hash_str(s: &str) -> [u32; 5] {
+    hash(s.as_bytes())
+}
+
+/// Hash a message with SHA-1 and return the digest as a lowercase hex string
+/// Every word is zero padded, so the string is always 40 characters long
+/// ```
+/// use sha::sha1::hash_hex;
+///
+/// println!("{}", hash_hex(b"hello world"));
+/// ```
+pub fn hash_hex(message: &[u8]) -> String {
+    hash(message).iter().map(|h| format!("{:08x}", h)).collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const _: () = assert!(BLOCK_SIZE == 64 && OUTPUT_SIZE == 20);
+
     #[test]
     fn padding_test() {
         let message = b"hello world";
@@ -147,7 +539,295 @@ mod tests {
     #[test]
     fn hash_multiple_chunks_test() {
         let message = "abc".repeat(5000);
-        let hash = hash(&message.as_bytes());
+        let hash = hash(message.as_bytes());
         assert_eq!(hash, [0x2ed315e2, 0x3eb0067f, 0xca759bce, 0x85eae2dc, 0xf180ac79]);
     }
+
+    #[test]
+    fn round_k_table_test() {
+        for t in 0..80 {
+            assert_eq!(ROUND_K[t], K[t / 20]);
+            assert_eq!(ROUND_K[t], get_k(t as u32));
+        }
+        assert_eq!([ROUND_K[0], ROUND_K[20], ROUND_K[40], ROUND_K[60]], [0x5A827999, 0x6ED9EBA1, 0x8F1BBCDC, 0xCA62C1D6]);
+    }
+
+    #[test]
+    fn hash_str_test() {
+        assert_eq!(hash_str("abc"), hash(b"abc"));
+    }
+
+    #[test]
+    fn hash_hex_test() {
+        assert_eq!(hash_hex(b"hello world"), "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed");
+    }
+
+    #[test]
+    fn empty_input_test() {
+        // an empty message still pads out to exactly one block
+        assert_eq!(message_padding(b"").len(), 64);
+        assert_eq!(hash_hex(b""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(Sha1::new().finalize(), hash(b""));
+    }
+
+    #[test]
+    fn hash_bytes_test() {
+        let bytes = hash_bytes(b"hello world");
+        assert_eq!(bytes, [
+            0x2a, 0xae, 0x6c, 0x35, 0xc9, 0x4f, 0xcf, 0xb4, 0x15, 0xdb,
+            0xe9, 0x5f, 0x40, 0x8b, 0x9c, 0xe9, 0x1e, 0xe8, 0x46, 0xed
+        ]);
+
+        let digest = Sha1Digest(bytes);
+        assert_eq!(digest.to_string(), "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed");
+        assert_eq!("2aae6c35c94fcfb415dbe95f408b9ce91ee846ed".parse::<Sha1Digest>().unwrap(), digest);
+        assert_eq!("2AAE6C35C94FCFB415DBE95F408B9CE91EE846ED".parse::<Sha1Digest>().unwrap(), digest);
+        assert!(matches!("2aae6c35".parse::<Sha1Digest>(), Err(ShaError::InvalidHex)));
+        assert!(matches!("zzae6c35c94fcfb415dbe95f408b9ce91ee846ed".parse::<Sha1Digest>(), Err(ShaError::InvalidHex)));
+    }
+
+    #[test]
+    fn git_blob_oid_test() {
+        assert_eq!(Sha1Digest(git_blob_oid(b"")).to_string(), "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+        assert_eq!(Sha1Digest(git_blob_oid(b"hello world\n")).to_string(), "3b18e512dba79e4c8300dd08aeb37f8e728b8dad");
+
+        let binary: Vec<u8> = (0..=255).collect();
+        assert_eq!(Sha1Digest(git_blob_oid(&binary)).to_string(), "c86626638e0bc8cf47ca49bb1525b40e9737ee64");
+    }
+
+    #[test]
+    fn length_boundary_test() {
+        // 55 bytes + 0x80 + 8 length bytes fills one block exactly, 56 needs a second block
+        let cases = [
+            (55, 64, "c1c8bbdc22796e28c0e15163d20899b65621d65a"),
+            (56, 128, "c2db330f6083854c99d4b5bfb6e8f29f201be699"),
+            (57, 128, "f08f24908d682555111be7ff6f004e78283d989a"),
+        ];
+        for (len, padded_len, expected) in cases {
+            let message = b"a".repeat(len);
+            assert_eq!(message_padding(&message).len(), padded_len);
+            assert_eq!(hash_hex(&message), expected);
+        }
+    }
+
+    #[test]
+    fn padded_len_test() {
+        for (len, padded) in [(0, 64), (55, 64), (56, 128), (63, 128), (64, 128)] {
+            assert_eq!(padded_len(len), padded);
+        }
+        for len in 0..300 {
+            assert_eq!(padded_len(len), message_padding(&vec![0; len]).len());
+        }
+    }
+
+    #[test]
+    fn exact_block_test() {
+        // 64 bytes (one block) pads to two blocks and 128 pads to three, the padding never fits in a full block
+        let cases = [
+            (64, 128, "0098ba824b5c16427bd7a1122a5a442a25ec644d"),
+            (128, 192, "ad5b3fdbcb526778c2839d2f151ea753995e26a0"),
+        ];
+        for (len, padded_len, expected) in cases {
+            let message = b"a".repeat(len);
+            assert_eq!(message_padding(&message).len(), padded_len);
+            assert_eq!(hash_hex(&message), expected);
+            assert_eq!(Sha1::new().chain(&message).finalize(), hash(&message));
+        }
+    }
+
+    #[test]
+    fn single_block_fast_path_test() {
+        // up to 55 bytes `hash` takes the single block path, after that the general one
+        for len in 0..58 {
+            let message = vec![0x61u8; len];
+            if len < 56 {
+                assert_eq!(single_block(&message)[..], message_padding(&message)[..]);
+            }
+            let mut hasher = Sha1::new();
+            hasher.update(&message);
+            assert_eq!(hash(&message), hasher.finalize());
+        }
+    }
+
+    #[test]
+    fn streaming_test() {
+        let message = b"a".repeat(1000);
+        let mut hasher = Sha1::new();
+        for piece in message.chunks(7) {
+            hasher.update(piece);
+        }
+        assert_eq!(hasher.finalize(), hash(&message));
+    }
+
+    #[test]
+    fn hash_vectored_test() {
+        assert_eq!(hash_vectored(&[b"hello ", b"world"]), hash(b"hello world"));
+    }
+
+    #[test]
+    fn hash_iter_test() {
+        let bytes: Vec<u8> = (0u8..=255).cycle().take(1000).collect();
+        assert_eq!(hash_iter((0u8..=255).cycle().take(1000)), hash(&bytes));
+        assert_eq!(hash_iter(std::iter::empty()), hash(b""));
+    }
+
+    #[test]
+    fn partial_blocks_test() {
+        // 3.5 blocks, fed in ways that hit both the buffered and the direct path
+        let message: Vec<u8> = (0..BLOCK_SIZE * 7 / 2).map(|i| i as u8).collect();
+        for split in [0, 1, BLOCK_SIZE / 2, BLOCK_SIZE, BLOCK_SIZE + 3, message.len()] {
+            let mut hasher = Sha1::new();
+            hasher.update(&message[..split]);
+            hasher.update(&message[split..]);
+            assert_eq!(hasher.finalize(), hash(&message));
+        }
+    }
+
+    #[test]
+    fn to_bytes_le_test() {
+        let hash = hash(b"hello world");
+        let be = state_to_bytes(&hash);
+        let le = state_to_bytes_le(&hash);
+        for (be_word, le_word) in be.chunks(4).zip(le.chunks(4)) {
+            let mut reversed = be_word.to_vec();
+            reversed.reverse();
+            assert_eq!(le_word, &reversed[..]);
+        }
+    }
+
+    #[test]
+    fn state_bytes_round_trip_test() {
+        let state = hash(b"hello world");
+        let bytes = hash_bytes(b"hello world");
+        assert_eq!(state_to_bytes(&state), bytes);
+        assert_eq!(bytes_to_state(&bytes), state);
+        assert_eq!(bytes_to_state(&state_to_bytes(&H)), H);
+    }
+
+    #[test]
+    fn finalize_into_test() {
+        let mut hasher = Sha1::new();
+        hasher.update(b"hello world");
+        let mut out = [0u8; 20];
+        hasher.clone().finalize_into(&mut out).unwrap();
+        assert_eq!(out, state_to_bytes(&hash(b"hello world")));
+
+        let mut short = [0u8; 19];
+        assert!(matches!(hasher.finalize_into(&mut short), Err(ShaError::InvalidLength)));
+    }
+
+    #[test]
+    fn finalize_verify_test() {
+        let mut expected = hash_bytes(b"hello world");
+        let mut hasher = Sha1::new();
+        hasher.update(b"hello world");
+        assert!(hasher.clone().finalize_verify(&expected));
+        assert!(!hasher.clone().finalize_verify(&expected[1..]));
+        expected[5] ^= 0x01;
+        assert!(!hasher.finalize_verify(&expected));
+    }
+
+    #[test]
+    fn digest_info_test() {
+        let digest = hash_bytes(b"abc");
+        let info = digest_info(&digest);
+        // the outer SEQUENCE length covers everything after its 2 byte header
+        assert_eq!(info[1] as usize, info.len() - 2);
+        assert_eq!(&info[15..], &digest[..]);
+        assert_eq!(&DIGEST_INFO_PREFIX[4..4 + OID.len()], &OID[..]);
+    }
+
+    #[test]
+    fn digest_from_array_test() {
+        let bytes = hash_bytes(b"hello world");
+        let digest: Sha1Digest = bytes.into();
+        assert_eq!(digest, Sha1Digest(bytes));
+        let back: [u8; 20] = digest.into();
+        assert_eq!(back, bytes);
+    }
+
+    #[test]
+    fn finalize_reset_test() {
+        let mut hasher = Sha1::new();
+        hasher.update(b"hello ");
+        hasher.update(b"world");
+        assert_eq!(hasher.finalize_reset(), hash(b"hello world"));
+
+        hasher.update(b"abc");
+        assert_eq!(hasher.finalize_reset(), hash(b"abc"));
+    }
+
+    #[test]
+    fn digest_test() {
+        let mut hasher = Sha1::new();
+        hasher.update(b"ab");
+        assert_eq!(hasher.digest(), hash(b"ab"));
+
+        hasher.update(b"c");
+        assert_eq!(hasher.finalize(), hash(b"abc"));
+    }
+
+    #[test]
+    fn bytes_processed_test() {
+        let mut hasher = Sha1::new();
+        assert_eq!(hasher.bytes_processed(), 0);
+        hasher.update(&[0u8; 100]);
+        hasher.update(&[0u8; 50]);
+        assert_eq!(hasher.bytes_processed(), 150);
+    }
+
+    #[cfg(feature = "debug-dump")]
+    #[test]
+    fn debug_state_test() {
+        // less than a block, so the hash values are still the initial ones
+        let dump = Sha1::new().chain(b"abc").debug_state();
+        assert!(dump.starts_with("h0=67452301 "));
+        assert_eq!(dump.matches(" h").count() + 1, 5);
+        assert!(dump.ends_with("buffer=3/64 length=3"));
+    }
+
+    #[test]
+    fn finalize_with_len_test() {
+        let mut hasher = Sha1::new();
+        hasher.update(&[0x61; 100]);
+        hasher.update(&[0x61; 50]);
+        assert_eq!(hasher.finalize_with_len(), (hash_bytes(&[0x61; 150]), 150));
+    }
+
+    #[test]
+    fn chain_test() {
+        assert_eq!(Sha1::new().chain(b"a").chain(b"b").finalize(), hash(b"ab"));
+        assert_eq!(Sha1::new().chain(b"").finalize(), hash(b""));
+    }
+
+    #[test]
+    fn update_str_test() {
+        let mut hasher = Sha1::new();
+        for part in ["héllo", " ", "wörld", "", " ✓"] {
+            hasher.update_str(part);
+        }
+        assert_eq!(hasher.finalize(), hash("héllo wörld ✓".as_bytes()));
+    }
+
+    #[test]
+    fn update_len_prefixed_test() {
+        let fields = |a: &[u8], b: &[u8]| {
+            let mut hasher = Sha1::new();
+            hasher.update_len_prefixed(a);
+            hasher.update_len_prefixed(b);
+            hasher.finalize()
+        };
+        assert_ne!(fields(b"ab", b"c"), fields(b"a", b"bc"));
+        assert_eq!(fields(b"ab", b"c"), hash(b"\0\0\0\0\0\0\0\x02ab\0\0\0\0\0\0\0\x01c"));
+    }
+
+    #[test]
+    fn new_with_prefix_test() {
+        let mut manual = Sha1::new();
+        manual.update(b"prefix:");
+        manual.update(b"data");
+        let mut prefixed = Sha1::new_with_prefix(b"prefix:");
+        prefixed.update(b"data");
+        assert_eq!(prefixed.finalize(), manual.finalize());
+    }
 }
\ No newline at end of file
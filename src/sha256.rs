@@ -1,18 +1,30 @@
 // sha256 as per https://csrc.nist.gov/files/pubs/fips/180-2/final/docs/fips180-2.pdf
+use crate::block::{blocks, remainder};
+use crate::digest::{digest_type, encode_hex, Digest};
+use crate::error::ShaError;
+
+/// How long `message_padding` makes a message of `message_len` bytes, without building it
+/// That's the message, the 0x80 byte and the 8 length bytes, rounded up to whole blocks
+pub fn padded_len(message_len: usize) -> usize {
+    (message_len + 9).div_ceil(BLOCK_SIZE) * BLOCK_SIZE
+}
 
 /// Following the standard, the message is to be padded as follows:
 /// 1. Append a 1 bit to the message
 /// 2. Append 0 bits until the length of the message is congruent to 448 mod 512
 /// 3. Append the length of the message in bits as a 64 bit number
+///
+/// A message that is exactly one block (64 bytes) becomes two blocks, and 128 bytes becomes three
 pub fn message_padding(message: &[u8]) -> Vec<u8> {
-    let message_len_bits = (message.len() * 8) as u64;
+    // widen before multiplying, a usize can overflow on 32 bit targets
+    let message_len_bits = message.len() as u64 * 8;
     let mut message_bytes = Vec::from(message);
 
     // append 1 bit as per the standard
     message_bytes.push(0x80); 
  
     let padding_len = (64 - (message_bytes.len() + 8) % 64) % 64;
-    message_bytes.extend(std::iter::repeat(0).take(padding_len));
+    message_bytes.extend(std::iter::repeat_n(0, padding_len));
 
     message_bytes.extend_from_slice(&message_len_bits.to_be_bytes());
 
@@ -20,26 +32,32 @@ pub fn message_padding(message: &[u8]) -> Vec<u8> {
 }
 
 // functions for the hash algorithm 
+#[inline]
 pub fn ch(x: u32, y: u32, z: u32) -> u32 {
     (x & y) ^ ((!x) & z)
 }
 
+#[inline]
 pub fn maj(x: u32, y: u32, z: u32) -> u32 {
     (x & y) ^ (x & z) ^ (y & z)
 }
 
+#[inline]
 pub fn big_sigma_0(x: u32) -> u32 {
     x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22)
 }
 
+#[inline]
 pub fn big_sigma_1(x: u32) -> u32 {
     x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25)
 }
 
+#[inline]
 pub fn small_sigma_0(x: u32) -> u32 {
     x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
 }
 
+#[inline]
 pub fn small_sigma_1(x: u32) -> u32 {
     x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
 }
@@ -70,7 +88,23 @@ pub const H: [u32; 8] = [
     0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19
 ];
 
+/// Size of a SHA-256 message block in bytes
+pub const BLOCK_SIZE: usize = 64;
+
+/// Size of a SHA-256 digest in bytes
+pub const OUTPUT_SIZE: usize = 32;
+
+/// DER encoding of the SHA-256 object identifier (2.16.840.1.101.3.4.2.1), tag and length included
+pub const OID: [u8; 11] = [0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+
+/// What goes in front of the digest in a DER `DigestInfo` (RFC 8017 section 9.2 note 1):
+/// the algorithm identifier with NULL parameters, then the header of the digest octet string
+pub const DIGEST_INFO_PREFIX: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04, 0x20,
+];
+
 /// Create a SHA-256 hash of a message
+/// This returns the 8 hash values as words, use `hash_bytes` to get the standard 32 byte digest instead
 /// 
 /// ## Example
 /// ```
@@ -86,69 +120,928 @@ pub const H: [u32; 8] = [
 /// println!();
 /// ```
 pub fn hash(message: &[u8]) -> [u32; 8] {
+    hash_with_iv(H, message)
+}
+
+/// Same as `hash` (padding and length included) but starting from `iv` instead of `H`
+/// For constructions that chain with their own initial values, like tree hashes.
+/// Unlike `Sha256::from_state` the length in the padding only counts `message`
+pub fn hash_with_iv(iv: [u32; 8], message: &[u8]) -> [u32; 8] {
+    // short messages pad out to a single block, so skip building the padded copy
+    if message.len() < BLOCK_SIZE - 8 {
+        let mut h_const = iv;
+        compress(&mut h_const, &single_block(message));
+        return h_const;
+    }
+
     let message_bytes = message_padding(message);
-    let mut h_const = H;
-
-    for chunk in message_bytes.chunks(64) {
-        let mut w = [0u32; 64];
-        for t in 0..16 {
-            w[t] = u32::from_be_bytes([
-                chunk[t * 4],
-                chunk[t * 4 + 1],
-                chunk[t * 4 + 2],
-                chunk[t * 4 + 3],
-            ]);
-        }
-        for t in 16..64 {
-            w[t] = small_sigma_1(w[t - 2])
-                .wrapping_add(w[t - 7])
-                .wrapping_add(small_sigma_0(w[t - 15]))
-                .wrapping_add(w[t - 16]);
-        }
-
-        let mut a = h_const[0];
-        let mut b = h_const[1];
-        let mut c = h_const[2];
-        let mut d = h_const[3];
-        let mut e = h_const[4];
-        let mut f = h_const[5];
-        let mut g = h_const[6];
-        let mut h = h_const[7];
-
-        for t in 0..64 {
-            let temp1 = h
-                .wrapping_add(big_sigma_1(e))
-                .wrapping_add(ch(e, f, g))
-                .wrapping_add(K[t])
-                .wrapping_add(w[t]);
-            let temp2 = big_sigma_0(a).wrapping_add(maj(a, b, c));
-            h = g;
-            g = f;
-            f = e;
-            e = d.wrapping_add(temp1);
-            d = c;
-            c = b;
-            b = a;
-            a = temp1.wrapping_add(temp2);
-        }
-
-        h_const[0] = h_const[0].wrapping_add(a);
-        h_const[1] = h_const[1].wrapping_add(b);
-        h_const[2] = h_const[2].wrapping_add(c);
-        h_const[3] = h_const[3].wrapping_add(d);
-        h_const[4] = h_const[4].wrapping_add(e);
-        h_const[5] = h_const[5].wrapping_add(f);
-        h_const[6] = h_const[6].wrapping_add(g);
-        h_const[7] = h_const[7].wrapping_add(h);
+    let mut h_const = iv;
+
+    for chunk in blocks(&message_bytes) {
+        compress(&mut h_const, chunk);
     }
 
     h_const
 }
 
+/// The padded form of a message shorter than `BLOCK_SIZE - 8`, which fits in one block
+/// Same layout as `message_padding`, just built on the stack
+fn single_block(message: &[u8]) -> [u8; BLOCK_SIZE] {
+    let mut block = [0u8; BLOCK_SIZE];
+    block[..message.len()].copy_from_slice(message);
+    block[message.len()] = 0x80;
+    block[BLOCK_SIZE - 8..].copy_from_slice(&((message.len() as u64) * 8).to_be_bytes());
+    block
+}
+
+/// Process a single 64 byte chunk of the padded message, updating the hash values in place
+fn compress(h_const: &mut [u32; 8], chunk: &[u8; BLOCK_SIZE]) {
+    let mut w = [0u32; 64];
+    // the chunk is 16 big-endian words, the zip stops after those
+    for (w_t, word) in w.iter_mut().zip(chunk.chunks_exact(4)) {
+        *w_t = u32::from_be_bytes(word.try_into().unwrap());
+    }
+    for t in 16..64 {
+        w[t] = small_sigma_1(w[t - 2])
+            .wrapping_add(w[t - 7])
+            .wrapping_add(small_sigma_0(w[t - 15]))
+            .wrapping_add(w[t - 16]);
+    }
+
+    let mut a = h_const[0];
+    let mut b = h_const[1];
+    let mut c = h_const[2];
+    let mut d = h_const[3];
+    let mut e = h_const[4];
+    let mut f = h_const[5];
+    let mut g = h_const[6];
+    let mut h = h_const[7];
+
+    for t in 0..64 {
+        let temp1 = h
+            .wrapping_add(big_sigma_1(e))
+            .wrapping_add(ch(e, f, g))
+            .wrapping_add(K[t])
+            .wrapping_add(w[t]);
+        let temp2 = big_sigma_0(a).wrapping_add(maj(a, b, c));
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+
+    h_const[0] = h_const[0].wrapping_add(a);
+    h_const[1] = h_const[1].wrapping_add(b);
+    h_const[2] = h_const[2].wrapping_add(c);
+    h_const[3] = h_const[3].wrapping_add(d);
+    h_const[4] = h_const[4].wrapping_add(e);
+    h_const[5] = h_const[5].wrapping_add(f);
+    h_const[6] = h_const[6].wrapping_add(g);
+    h_const[7] = h_const[7].wrapping_add(h);
+}
+
+/// Convert the hash values into the standard SHA-256 digest bytes (each word big-endian)
+pub fn state_to_bytes(state: &[u32; 8]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, h) in bytes.chunks_exact_mut(4).zip(state.iter()) {
+        chunk.copy_from_slice(&h.to_be_bytes());
+    }
+    bytes
+}
+
+/// Convert digest bytes back into the hash values, the opposite of `state_to_bytes`
+pub fn bytes_to_state(bytes: &[u8; 32]) -> [u32; 8] {
+    let mut state = [0u32; 8];
+    for (h, chunk) in state.iter_mut().zip(bytes.chunks_exact(4)) {
+        *h = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+    state
+}
+
+/// Same as `state_to_bytes` but with each word in little-endian order
+/// Big-endian is what the standard uses and what other tools print, so only use this
+/// for formats that store the words the other way around
+pub fn state_to_bytes_le(state: &[u32; 8]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, h) in bytes.chunks_exact_mut(4).zip(state.iter()) {
+        chunk.copy_from_slice(&h.to_le_bytes());
+    }
+    bytes
+}
+
+digest_type!(
+    /// A SHA-256 digest as its 32 standard (big-endian) bytes
+    Sha256Digest, 32
+);
+
+/// Sort digests into their canonical byte order (same as sorting their hex strings),
+/// for building something like a Merkle root that mustn't depend on the input order
+pub fn sort_digests(digests: &mut [Sha256Digest]) {
+    digests.sort_unstable();
+}
+
+/// Hash a message and return the 32 digest bytes instead of the hash words
+pub fn hash_bytes(message: &[u8]) -> [u8; 32] {
+    state_to_bytes(&hash(message))
+}
+
+/// The DER `DigestInfo` for a SHA-256 digest, as signed in PKCS#1 v1.5 signatures
+/// Panics if `digest` isn't `OUTPUT_SIZE` bytes, the prefix encodes that length
+pub fn digest_info(digest: &[u8]) -> Vec<u8> {
+    assert_eq!(digest.len(), OUTPUT_SIZE, "not a SHA-256 digest");
+    [&DIGEST_INFO_PREFIX[..], digest].concat()
+}
+
+/// Everything a `Sha256` hasher is in the middle of, for checkpointing a long hash and picking it up again later
+/// (after a restart for example). Get one with `Sha256::export_state`, turn it back into a hasher with `Sha256::import_state`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HasherState {
+    /// The running hash values
+    pub h: [u32; 8],
+    /// The partial block waiting for more data, only the first `buffer_len` bytes mean anything
+    pub buffer: [u8; BLOCK_SIZE],
+    /// How much of `buffer` is filled
+    pub buffer_len: usize,
+    /// Total bytes added so far
+    pub length: u64,
+}
+
+/// Streaming SHA-256 hasher, for when the message isn't available all at once
+/// Feeding the message in pieces gives the same result as `hash` on the whole message.
+/// All of its state is in fixed size arrays, so `update` and `finalize` never allocate
+/// ```
+/// use sha::sha256::{hash, Sha256};
+///
+/// let mut hasher = Sha256::new();
+/// hasher.update(b"hello ");
+/// hasher.update(b"world");
+/// assert_eq!(hasher.finalize(), hash(b"hello world"));
+/// ```
+///
+/// There's no "already finalized" state that needs guarding: `finalize` takes the hasher by value,
+/// `finalize_reset` leaves a fresh hasher behind and `digest` works on a copy. Using it after `finalize` won't compile
+/// ```compile_fail,E0382
+/// use sha::sha256::Sha256;
+///
+/// let mut hasher = Sha256::new();
+/// hasher.update(b"hello");
+/// let digest = hasher.finalize();
+/// hasher.update(b"world");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sha256 {
+    h: [u32; 8],
+    buffer: [u8; BLOCK_SIZE],
+    buffer_len: usize,
+    length: u64,
+}
+
+impl Sha256 {
+    /// Create a new hasher starting from the initial hash values
+    pub fn new() -> Self {
+        Self {
+            h: H,
+            buffer: [0; BLOCK_SIZE],
+            buffer_len: 0,
+            length: 0,
+        }
+    }
+
+    /// Resume hashing from a midstate saved with `midstate`
+    /// `bytes_processed` is how much of the message the state already covers, it's needed for the final length.
+    /// The hash values only exist between blocks, so this fails if `bytes_processed` isn't a multiple of `BLOCK_SIZE`
+    pub fn from_state(state: [u32; 8], bytes_processed: u64) -> Result<Self, ShaError> {
+        if !bytes_processed.is_multiple_of(BLOCK_SIZE as u64) {
+            return Err(ShaError::InvalidLength);
+        }
+
+        Ok(Self {
+            h: state,
+            buffer: [0; BLOCK_SIZE],
+            buffer_len: 0,
+            length: bytes_processed,
+        })
+    }
+
+    /// The hash values and the number of bytes absorbed so far, if the hasher is on a block boundary
+    /// This is useful when lots of messages share a prefix, the prefix only has to be compressed once
+    /// and then each message can start from `from_state`. Returns `None` if part of a block is still buffered
+    /// ```
+    /// use sha::sha256::{hash, Sha256};
+    ///
+    /// let prefix = [0x42u8; 64];
+    /// let mut hasher = Sha256::new();
+    /// hasher.update(&prefix);
+    /// let (state, len) = hasher.midstate().unwrap();
+    ///
+    /// let mut resumed = Sha256::from_state(state, len).unwrap();
+    /// resumed.update(b"suffix");
+    /// assert_eq!(resumed.finalize(), hash(&[&prefix[..], b"suffix"].concat()));
+    /// ```
+    pub fn midstate(&self) -> Option<([u32; 8], u64)> {
+        if self.buffer_len != 0 {
+            return None;
+        }
+        Some((self.h, self.length))
+    }
+
+    /// Snapshot the whole hasher, partial block included, unlike `midstate` this works at any point
+    pub fn export_state(&self) -> HasherState {
+        HasherState {
+            h: self.h,
+            buffer: self.buffer,
+            buffer_len: self.buffer_len,
+            length: self.length,
+        }
+    }
+
+    /// Carry on from a state saved with `export_state`
+    /// Fails if the buffer fill level doesn't fit the block size or doesn't agree with `length`
+    pub fn import_state(state: HasherState) -> Result<Self, ShaError> {
+        if state.buffer_len >= BLOCK_SIZE || state.length % BLOCK_SIZE as u64 != state.buffer_len as u64 {
+            return Err(ShaError::InvalidLength);
+        }
+
+        Ok(Self {
+            h: state.h,
+            buffer: state.buffer,
+            buffer_len: state.buffer_len,
+            length: state.length,
+        })
+    }
+
+    /// Add more of the message, anything that doesn't fill a whole block is kept until the next call
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        // top up a partly filled buffer first
+        if self.buffer_len > 0 {
+            let take = (BLOCK_SIZE - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+
+            if self.buffer_len < BLOCK_SIZE {
+                return;
+            }
+            compress(&mut self.h, &self.buffer);
+            self.buffer_len = 0;
+        }
+
+        // whole blocks can be compressed straight from the input, no need to copy them into the buffer
+        for block in blocks(data) {
+            compress(&mut self.h, block);
+        }
+
+        let rest = remainder::<BLOCK_SIZE>(data);
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
+    }
+
+    /// Compress exactly one block straight into the hash values, skipping the buffer, and count it as 64 bytes
+    /// This is for low-level use like doing your own padding. It's only the same as `update` when nothing is
+    /// buffered, mixing the two with a partial block pending gives a wrong hash and that's on the caller
+    pub fn update_block(&mut self, block: &[u8; BLOCK_SIZE]) {
+        compress(&mut self.h, block);
+        self.length = self.length.wrapping_add(BLOCK_SIZE as u64);
+    }
+
+    /// A hasher that has already absorbed `prefix`, for protocols that hash a fixed prefix before the data
+    /// To skip re-hashing a long prefix every time, make this once and `clone` it per message
+    pub fn new_with_prefix(prefix: &[u8]) -> Self {
+        let mut hasher = Self::new();
+        hasher.update(prefix);
+        hasher
+    }
+
+    /// `update` that takes and returns the hasher, for writing it all in one expression
+    /// ```
+    /// use sha::sha256::{hash, Sha256};
+    ///
+    /// assert_eq!(Sha256::new().chain(b"a").chain(b"b").finalize(), hash(b"ab"));
+    /// ```
+    pub fn chain(mut self, data: &[u8]) -> Self {
+        self.update(data);
+        self
+    }
+
+    /// Add a string to the message, this hashes its UTF-8 encoding, same as `update(s.as_bytes())`
+    pub fn update_str(&mut self, s: &str) {
+        self.update(s.as_bytes());
+    }
+
+    /// Add `data` as one field: its length as 8 bytes big-endian, then the bytes
+    /// Fields written this way can't run into each other, `("ab", "c")` and `("a", "bc")` hash differently
+    pub fn update_len_prefixed(&mut self, data: &[u8]) {
+        self.update(&(data.len() as u64).to_be_bytes());
+        self.update(data);
+    }
+
+    /// Pad whatever is left in the buffer (same as `message_padding`) and return the hash
+    pub fn finalize(mut self) -> [u32; 8] {
+        // the byte count is a u64, so the bit length is right up to the 2^64 bit limit of SHA-256
+        let message_len_bits = self.length.wrapping_mul(8);
+
+        self.buffer[self.buffer_len] = 0x80;
+        self.buffer[self.buffer_len + 1..].fill(0);
+
+        // not enough room left for the length, so it goes in an extra block
+        if self.buffer_len >= BLOCK_SIZE - 8 {
+            compress(&mut self.h, &self.buffer);
+            self.buffer = [0; BLOCK_SIZE];
+        }
+
+        self.buffer[BLOCK_SIZE - 8..].copy_from_slice(&message_len_bits.to_be_bytes());
+        compress(&mut self.h, &self.buffer);
+
+        self.h
+    }
+
+    /// Finish the hash and return the digest bytes along with the total number of bytes hashed
+    pub fn finalize_with_len(self) -> ([u8; 32], u64) {
+        let len = self.length;
+        (state_to_bytes(&self.finalize()), len)
+    }
+
+    /// The current hash values, buffer fill level and length as readable text, for debugging and for
+    /// following the algorithm along by hand, like `h0=67452301 ... buffer=3/64 length=3`
+    #[cfg(feature = "debug-dump")]
+    pub fn debug_state(&self) -> String {
+        let mut dump = String::new();
+        for (i, word) in self.h.iter().enumerate() {
+            dump.push_str(&format!("h{}={:08x} ", i, word));
+        }
+        dump.push_str(&format!("buffer={}/{} length={}", self.buffer_len, BLOCK_SIZE, self.length));
+        dump
+    }
+
+    /// How many bytes have been added so far, across all the `update` calls
+    pub fn bytes_processed(&self) -> u64 {
+        self.length
+    }
+
+    /// The hash of everything added so far, without finishing the hasher
+    /// The padding is done on a copy, so more data can still be added afterwards
+    pub fn digest(&self) -> [u32; 8] {
+        self.clone().finalize()
+    }
+
+    /// Digest bytes of everything so far, like `digest` but checking the state first, the hasher can keep going afterwards
+    /// Fails with `InvalidLength` if the buffered byte count doesn't line up with the total length, and with
+    /// `MessageTooLong` if the bit length no longer fits the 64-bit length field
+    pub fn try_finalize(&self) -> Result<[u8; 32], ShaError> {
+        if self.buffer_len >= BLOCK_SIZE || self.length % BLOCK_SIZE as u64 != self.buffer_len as u64 {
+            return Err(ShaError::InvalidLength);
+        }
+        if self.length > u64::MAX / 8 {
+            return Err(ShaError::MessageTooLong);
+        }
+        Ok(state_to_bytes(&self.digest()))
+    }
+
+    /// Finish the hash and reset the hasher back to the initial hash values, ready for the next message
+    pub fn finalize_reset(&mut self) -> [u32; 8] {
+        std::mem::take(self).finalize()
+    }
+
+    /// Finish the hash and write the digest bytes (big-endian, same as `state_to_bytes`) into `out`
+    /// Fails if `out` is shorter than `OUTPUT_SIZE`, any bytes after the digest are left alone
+    pub fn finalize_into(self, out: &mut [u8]) -> Result<(), ShaError> {
+        if out.len() < OUTPUT_SIZE {
+            return Err(ShaError::InvalidLength);
+        }
+        out[..OUTPUT_SIZE].copy_from_slice(&state_to_bytes(&self.finalize()));
+        Ok(())
+    }
+
+    /// Finish the hash and check it against `expected` digest bytes, in constant time
+    /// A wrong length is just a mismatch
+    pub fn finalize_verify(self, expected: &[u8]) -> bool {
+        crate::ct::eq(&state_to_bytes(&self.finalize()), expected)
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Digest for Sha256 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+    const OUTPUT_SIZE: usize = OUTPUT_SIZE;
+    type Output = [u8; 32];
+
+    fn new() -> Self {
+        Sha256::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha256::update(self, data);
+    }
+
+    fn finalize_bytes(self) -> Self::Output {
+        state_to_bytes(&self.finalize())
+    }
+}
+
+/// SHA-256 behind the `std::hash::Hasher` trait, so it can be used by anything generic over `Hasher`
+/// (for example a `HashMap` with `BuildHasherDefault<ShaHasher>`)
+///
+/// `Hasher::finish` can only return a `u64`, so it gives the first 8 bytes of the digest as a big-endian number.
+/// That throws away most of the hash and is nowhere near as collision resistant as the full digest,
+/// use `digest` when the real SHA-256 value is needed
+#[derive(Clone, Debug, Default)]
+pub struct ShaHasher(Sha256);
+
+impl ShaHasher {
+    /// Create a new hasher starting from the initial hash values
+    pub fn new() -> Self {
+        Self(Sha256::new())
+    }
+
+    /// The full SHA-256 of everything written so far, the hasher can keep being written to afterwards
+    pub fn digest(&self) -> [u32; 8] {
+        self.0.digest()
+    }
+
+    /// The full digest bytes of everything written so far, `finish` is the first 8 of these
+    pub fn finish_full(&self) -> [u8; 32] {
+        state_to_bytes(&self.digest())
+    }
+}
+
+impl std::hash::Hasher for ShaHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let digest = self.digest();
+        ((digest[0] as u64) << 32) | digest[1] as u64
+    }
+}
+
+/// Writer that passes everything through to `inner` and hashes it on the way,
+/// for checksumming data while it's being saved or sent somewhere
+/// ```
+/// use std::io::Write;
+/// use sha::sha256::{hash_bytes, TeeHasher};
+///
+/// let mut tee = TeeHasher::new(Vec::new());
+/// tee.write_all(b"hello world").unwrap();
+/// let (written, digest) = tee.finish();
+/// assert_eq!(written, b"hello world");
+/// assert_eq!(digest, hash_bytes(b"hello world"));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct TeeHasher<W: std::io::Write> {
+    inner: W,
+    hasher: Sha256,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> TeeHasher<W> {
+    /// Wrap `inner`, nothing has been hashed yet
+    pub fn new(inner: W) -> Self {
+        Self { inner, hasher: Sha256::new() }
+    }
+
+    /// Give back the inner writer along with the digest of everything written to it
+    /// Doesn't flush, call `flush` first if the inner writer buffers
+    pub fn finish(self) -> (W, [u8; 32]) {
+        (self.inner, state_to_bytes(&self.hasher.finalize()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> std::io::Write for TeeHasher<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // only hash what the inner writer actually took, so the digest always matches what was written
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Writer that passes everything through to `inner` and keeps a running hash of it, which can be looked at
+/// any time with `digest` (unlike `TeeHasher`, which only gives the digest at the end). Handy for log integrity
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct HashingWriter<W: std::io::Write> {
+    inner: W,
+    hasher: Sha256,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> HashingWriter<W> {
+    /// Wrap `inner`, nothing has been hashed yet
+    pub fn new(inner: W) -> Self {
+        Self { inner, hasher: Sha256::new() }
+    }
+
+    /// Digest bytes of everything written so far, writing can carry on afterwards
+    pub fn digest(&self) -> [u8; 32] {
+        state_to_bytes(&self.hasher.digest())
+    }
+
+    /// Give back the inner writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> std::io::Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Hash everything `reader` gives until EOF, calling `on_progress` with the total number of bytes read so far
+/// after every read, and once more at the end with the final total. The totals never go down
+/// ```
+/// use sha::sha256::{hash_bytes, hash_reader_with_progress};
+///
+/// let mut reader: &[u8] = b"hello world";
+/// let digest = hash_reader_with_progress(&mut reader, |read| println!("{read} bytes")).unwrap();
+/// assert_eq!(digest, hash_bytes(b"hello world"));
+/// ```
+#[cfg(feature = "std")]
+pub fn hash_reader_with_progress<R: std::io::Read>(
+    reader: &mut R,
+    mut on_progress: impl FnMut(u64),
+) -> Result<[u8; 32], ShaError> {
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+        hasher.update(&buffer[..read]);
+        on_progress(hasher.bytes_processed());
+    }
+    on_progress(hasher.bytes_processed());
+    Ok(state_to_bytes(&hasher.finalize()))
+}
+
+/// Hash a JSON value in a canonical form: object keys sorted (at every level), no whitespace, strings and numbers
+/// written the way `serde_json` writes them. Documents that only differ in key order or formatting hash the same
+/// Keys are sorted by their UTF-8 bytes, so this isn't byte for byte RFC 8785 (JCS) when keys go beyond ASCII
+#[cfg(feature = "serde_json")]
+pub fn hash_canonical_json(value: &serde_json::Value) -> [u8; 32] {
+    fn write_canonical(value: &serde_json::Value, out: &mut String) {
+        match value {
+            serde_json::Value::Object(map) => {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                out.push('{');
+                for (i, (key, value)) in entries.into_iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                    out.push(':');
+                    write_canonical(value, out);
+                }
+                out.push('}');
+            }
+            serde_json::Value::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_canonical(item, out);
+                }
+                out.push(']');
+            }
+            other => out.push_str(&other.to_string()),
+        }
+    }
+
+    let mut canonical = String::new();
+    write_canonical(value, &mut canonical);
+    hash_bytes(canonical.as_bytes())
+}
+
+/// Hash what a gzip stream decompresses to, streaming so the decompressed data is never all in memory
+/// A truncated or corrupt stream gives the decoder's error as `ShaError::Io`
+#[cfg(feature = "flate2")]
+pub fn hash_gzip_decompressed<R: std::io::Read>(reader: R) -> Result<[u8; 32], ShaError> {
+    hash_reader_with_progress(&mut flate2::read::GzDecoder::new(reader), |_| {})
+}
+
+/// Async version of `hash_reader_with_progress` (without the progress) for tokio readers
+/// Reads a buffer at a time and feeds the normal streaming hasher, readers that only give a few bytes per poll are fine
+#[cfg(feature = "tokio")]
+pub async fn hash_async_reader<R: tokio::io::AsyncRead + Unpin>(reader: &mut R) -> Result<[u8; 32], ShaError> {
+    use tokio::io::AsyncReadExt;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        // interrupted reads are retried, same as the blocking version
+        let read = match reader.read(&mut buffer).await {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+        hasher.update(&buffer[..read]);
+    }
+    Ok(state_to_bytes(&hasher.finalize()))
+}
+
+/// Hash the file at `path` and compare it to the `expected` digest bytes in constant time
+/// A wrong length `expected` is just `Ok(false)`, errors are only for when the file can't be read
+#[cfg(feature = "std")]
+pub fn verify_file<P: AsRef<std::path::Path>>(path: P, expected: &[u8]) -> Result<bool, ShaError> {
+    let mut file = std::fs::File::open(path)?;
+    let digest = hash_reader_with_progress(&mut file, |_| {})?;
+    Ok(crate::ct::eq(&digest, expected))
+}
+
+/// One digest for a whole directory tree, the same on any machine with the same files
+/// Every entry under `root` is put in order of its relative path (components joined with `/`, sorted by byte),
+/// and the root digest is the SHA-256 of one record per entry:
+/// - a regular file is `f`, the length-prefixed path, then the SHA-256 of its contents
+/// - a symlink is `l`, the length-prefixed path, then the length-prefixed link target. Links aren't followed,
+///   so a link out of the tree or a dangling one hashes fine
+///
+/// Directories only show up through the paths of what's in them, so empty directories don't count, and other
+/// kinds of entries (sockets, devices) are skipped. Paths and link targets that aren't UTF-8 give an
+/// `InvalidData` I/O error, since their bytes would depend on the platform
+#[cfg(feature = "std")]
+pub fn hash_dir<P: AsRef<std::path::Path>>(root: P) -> Result<[u8; 32], ShaError> {
+    fn utf8(path: &std::path::Path) -> std::io::Result<&str> {
+        path.to_str().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, format!("path isn't UTF-8: {}", path.display()))
+        })
+    }
+
+    fn walk(
+        dir: &std::path::Path,
+        prefix: &str,
+        entries: &mut Vec<(String, std::path::PathBuf)>,
+    ) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let name = utf8(std::path::Path::new(&entry.file_name()))?.to_owned();
+            let relative = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
+            if entry.file_type()?.is_dir() {
+                walk(&path, &relative, entries)?;
+            } else {
+                entries.push((relative, path));
+            }
+        }
+        Ok(())
+    }
+
+    let mut entries = Vec::new();
+    walk(root.as_ref(), "", &mut entries)?;
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut hasher = Sha256::new();
+    for (relative, path) in entries {
+        let file_type = std::fs::symlink_metadata(&path)?.file_type();
+        if file_type.is_symlink() {
+            let target = std::fs::read_link(&path)?;
+            hasher.update(b"l");
+            hasher.update_len_prefixed(relative.as_bytes());
+            hasher.update_len_prefixed(utf8(&target)?.as_bytes());
+        } else if file_type.is_file() {
+            let contents = hash_reader_with_progress(&mut std::fs::File::open(&path)?, |_| {})?;
+            hasher.update(b"f");
+            hasher.update_len_prefixed(relative.as_bytes());
+            hasher.update(&contents);
+        }
+    }
+    Ok(state_to_bytes(&hasher.finalize()))
+}
+
+/// Hash a file by memory mapping it instead of reading it through a buffer, can be faster for big files
+/// Empty files can't be mapped on every platform, so those just give the digest of empty input
+#[cfg(feature = "mmap")]
+pub fn hash_mmap<P: AsRef<std::path::Path>>(path: P) -> Result<[u8; 32], ShaError> {
+    let file = std::fs::File::open(path)?;
+    if file.metadata()?.len() == 0 {
+        return Ok(hash_bytes(b""));
+    }
+    // SAFETY: the map is only read while it's alive, if another process truncates or changes the file
+    // meanwhile the digest is garbage (or the read faults), same as for any other mmap reader
+    let map = unsafe { memmap2::Mmap::map(&file)? };
+    let mut hasher = Sha256::new();
+    hasher.update(&map);
+    Ok(state_to_bytes(&hasher.finalize()))
+}
+
+/// Hash a message made up of several parts without joining them together first
+/// The result is the same as hashing the concatenation of `parts`
+pub fn hash_vectored(parts: &[&[u8]]) -> [u32; 8] {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize()
+}
+
+/// Like `hash_vectored` but for any iterator of borrowed chunks, like the segments a parser hands out
+/// The result is the same as hashing the chunks joined together
+pub fn hash_chunks<'a, I: IntoIterator<Item = &'a [u8]>>(chunks: I) -> [u32; 8] {
+    let mut hasher = Sha256::new();
+    for chunk in chunks {
+        hasher.update(chunk);
+    }
+    hasher.finalize()
+}
+
+/// Tagged hash as used by BIP-340: `SHA256(SHA256(tag) || SHA256(tag) || message)`
+/// Using a different tag for each purpose keeps hashes from one context being valid in another.
+/// The two tag hashes make up exactly one block, so it is compressed once before the message is added
+pub fn tagged_hash(tag: &[u8], message: &[u8]) -> [u8; 32] {
+    let tag_hash = hash_bytes(tag);
+
+    let mut hasher = Sha256::new();
+    hasher.update(&tag_hash);
+    hasher.update(&tag_hash);
+    hasher.update(message);
+    state_to_bytes(&hasher.finalize())
+}
+
+/// `SHA256(SHA256(message))`, the double hash Bitcoin uses for block and transaction ids
+/// The second hash is over the 32 digest bytes of the first
+pub fn double_sha256(message: &[u8]) -> [u8; 32] {
+    hash_bytes(&hash_bytes(message))
+}
+
+/// Hash `buf[range]`, but with an error instead of a panic if the range is backwards or past the end
+/// Meant for parsers working with offsets from untrusted input
+pub fn hash_range(buf: &[u8], range: std::ops::Range<usize>) -> Result<[u8; 32], ShaError> {
+    buf.get(range).map(hash_bytes).ok_or(ShaError::InvalidLength)
+}
+
+/// Keyed hash `SHA256(len(key) || key || message)`, with the key length as 8 bytes big-endian
+/// The length prefix means a different split between key and message can't give the same input,
+/// which plain `SHA256(key || message)` allows. This is NOT HMAC and still has SHA-256's length extension
+/// property, use `hmac::hmac_sha256` where a real MAC is needed
+pub fn keyed_hash(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update_len_prefixed(key);
+    hasher.update(message);
+    state_to_bytes(&hasher.finalize())
+}
+
+/// Salted hash `SHA256(len(salt) || salt || message)` for storing values, with the salt length as 8 bytes big-endian
+/// The length prefix keeps bytes from moving between the salt and the message unnoticed.
+/// It's the same encoding as `keyed_hash`, the separate name is there to say what the input is for
+pub fn salted_hash(salt: &[u8], message: &[u8]) -> [u8; 32] {
+    keyed_hash(salt, message)
+}
+
+/// `SHA256(SHA512(message))`, the SHA-256 of the 64 SHA-512 digest bytes
+pub fn compose_256_512(message: &[u8]) -> [u8; 32] {
+    hash_bytes(&crate::sha512::hash_bytes(message))
+}
+
+/// Order independent hash of a collection: every item is hashed, the digests are sorted and then hashed together
+/// as a `tagged_hash` (tag `sha/set_hash`), so the result can't be confused with a hash of the digests made some other way.
+/// Duplicates count, so `[a, a]` and `[a]` are different
+pub fn set_hash(items: &[&[u8]]) -> [u8; 32] {
+    let mut digests: Vec<Sha256Digest> = items.iter().map(|item| Sha256Digest(hash_bytes(item))).collect();
+    sort_digests(&mut digests);
+    let joined: Vec<u8> = digests.iter().flat_map(|digest| digest.0).collect();
+    tagged_hash(b"sha/set_hash", &joined)
+}
+
+/// Hash a list of items so the boundaries between them count, unlike `hash_vectored`
+/// Each item is written as its length (8 bytes, big-endian) followed by its bytes,
+/// so `["ab", "c"]` and `["a", "bc"]` give different hashes, and so does changing the order
+pub fn concat_hash(items: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for item in items {
+        hasher.update_len_prefixed(item);
+    }
+    state_to_bytes(&hasher.finalize())
+}
+
+/// The first `N` bytes of the digest, for short ids and display hashes
+/// `N` can be anything from 0 up to `OUTPUT_SIZE`, this is checked at compile time.
+/// Keep in mind a truncated hash is only as collision resistant as its length
+/// ```
+/// use sha::sha256::truncated;
+///
+/// let short: [u8; 12] = truncated(b"hello world");
+/// ```
+/// Asking for more bytes than the digest has doesn't compile
+/// ```compile_fail
+/// let too_long: [u8; 33] = sha::sha256::truncated(b"hello world");
+/// ```
+pub fn truncated<const N: usize>(message: &[u8]) -> [u8; N] {
+    const { assert!(N <= OUTPUT_SIZE, "can't truncate a digest to more than OUTPUT_SIZE bytes") };
+
+    let mut out = [0u8; N];
+    out.copy_from_slice(&hash_bytes(message)[..N]);
+    out
+}
+
+/// First 8 digest bytes as a big-endian `u64`, for cache keys and the like
+/// Only 64 bits are left, so expect a collision somewhere around 2^32 distinct messages (birthday bound), and
+/// anyone can find one with that much work. Fine for deduplicating your own data, not for anything adversarial
+pub fn fingerprint64(message: &[u8]) -> u64 {
+    u64::from_be_bytes(truncated::<8>(message))
+}
+
+/// Hash bytes from an iterator (a decompressor or some other generator) without collecting them first
+/// The bytes are gathered a block at a time before being passed to the hasher
+pub fn hash_iter<I: IntoIterator<Item = u8>>(iter: I) -> [u32; 8] {
+    let mut hasher = Sha256::new();
+    let mut block = [0u8; BLOCK_SIZE];
+    let mut block_len = 0;
+
+    for byte in iter {
+        block[block_len] = byte;
+        block_len += 1;
+        if block_len == BLOCK_SIZE {
+            hasher.update(&block);
+            block_len = 0;
+        }
+    }
+
+    hasher.update(&block[..block_len]);
+    hasher.finalize()
+}
+
+/// Hash each message separately, the results are in the same order as `messages`
+pub fn hash_all(messages: &[&[u8]]) -> Vec<[u32; 8]> {
+    messages.iter().map(|message| hash(message)).collect()
+}
+
+/// `hash_all` split over `threads` scoped threads (0 counts as 1), giving digest bytes in the same order as `messages`
+/// Each thread gets one contiguous run of messages, so it pays off for big batches of similar sized messages
+#[cfg(feature = "std")]
+pub fn hash_many_parallel(messages: &[&[u8]], threads: usize) -> Vec<[u8; 32]> {
+    let per_thread = messages.len().div_ceil(threads.max(1)).max(1);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = messages
+            .chunks(per_thread)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(|message| hash_bytes(message)).collect::<Vec<_>>()))
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    })
+}
+
+/// Format a digest the way `openssl dgst -sha256` prints it: `SHA256(<label>)= <hex>`
+/// `label` is whatever was hashed, usually a file name or `stdin`.
+/// This is the OpenSSL 1.x style, which is what most existing scripts parse
+pub fn openssl_format(label: &str, digest: &[u8]) -> String {
+    format!("SHA256({})= {}", label, encode_hex(digest))
+}
+
+/// Hash a string slice with SHA-256
+/// Note that this hashes the UTF-8 encoding of the string, which is what
+/// other implementations do when given text, so the result is the same as `hash(s.as_bytes())`
+pub fn hash_str(s: &str) -> [u32; 8] {
+    hash(s.as_bytes())
+}
+
+/// Hash a message with SHA-256 and return the digest as a lowercase hex string
+/// Every word is zero padded, so the string is always 64 characters long
+/// ```
+/// use sha::sha256::hash_hex;
+///
+/// println!("{}", hash_hex(b"hello world"));
+/// ```
+pub fn hash_hex(message: &[u8]) -> String {
+    hash(message).iter().map(|h| format!("{:08x}", h)).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const _: () = assert!(BLOCK_SIZE == 64 && OUTPUT_SIZE == 32);
+
     #[test]
     fn padding_test() {
         let message = b"hello world";
@@ -174,6 +1067,784 @@ mod tests {
             0x41edece4, 0x2d63e8d9, 0xbf515a9b, 0xa6932e1c, 0x20cbc9f5, 0xa5d13464, 0x5adb5db1, 0xb9737ea3
         ]);
     }
+
+    #[test]
+    fn hash_with_iv_test_sha256() {
+        let message = b"hello world";
+        assert_eq!(hash_with_iv(H, message), hash(message));
+        assert_ne!(hash_with_iv([0; 8], message), hash(message));
+    }
+
+    #[test]
+    fn hash_str_test_sha256() {
+        assert_eq!(hash_str("abc"), hash(b"abc"));
+    }
+
+    #[test]
+    fn hash_hex_test_sha256() {
+        assert_eq!(hash_hex(b"hello world"), "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9");
+    }
+
+    #[test]
+    fn openssl_format_test_sha256() {
+        // matches `printf 'hello world' | openssl dgst -sha256` on OpenSSL 1.x
+        assert_eq!(
+            openssl_format("stdin", &hash_bytes(b"hello world")),
+            "SHA256(stdin)= b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+    }
+
+    #[test]
+    fn digest_as_key_test_sha256() {
+        use std::collections::{BTreeSet, HashMap};
+
+        let a = Sha256Digest(hash_bytes(b"a"));
+        let b = Sha256Digest(hash_bytes(b"b"));
+        let c = Sha256Digest(hash_bytes(b"c"));
+
+        let mut names = HashMap::new();
+        names.insert(a, "a");
+        names.insert(b, "b");
+        names.insert(c, "c");
+        assert_eq!(names[&b], "b");
+        assert_eq!(names.get(&Sha256Digest(hash_bytes(b"c"))), Some(&"c"));
+
+        let sorted: BTreeSet<_> = [a, b, c].into_iter().collect();
+        let sorted: Vec<String> = sorted.iter().map(|d| d.to_string()).collect();
+        // c, b, a hash to 2e7d..., 3e23..., ca97...
+        assert_eq!(sorted, [c.to_string(), b.to_string(), a.to_string()]);
+        assert!(sorted.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn digest_from_array_test_sha256() {
+        let bytes = hash_bytes(b"hello world");
+        let digest: Sha256Digest = bytes.into();
+        assert_eq!(digest, Sha256Digest(bytes));
+        let back: [u8; 32] = digest.into();
+        assert_eq!(back, bytes);
+    }
+
+    #[test]
+    fn digest_eq_array_test_sha256() {
+        let digest = Sha256Digest(hash_bytes(b"abc"));
+        let expected = [
+            0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22, 0x23,
+            0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00, 0x15, 0xad,
+        ];
+        assert_eq!(digest, expected);
+        assert_eq!(expected, digest);
+        assert_ne!(digest, [0u8; 32]);
+        assert_ne!([0u8; 32], digest);
+    }
+
+    #[test]
+    fn try_finalize_test_sha256() {
+        let mut hasher = Sha256::new();
+        hasher.update(b"hello ");
+        let shared = &hasher;
+        assert_eq!(shared.try_finalize().unwrap(), hash_bytes(b"hello "));
+        hasher.update(b"world");
+        assert_eq!(hasher.try_finalize().unwrap(), hash_bytes(b"hello world"));
+
+        // buffered bytes that don't match the length, like a state that got corrupted on the way
+        let corrupt = Sha256 { buffer_len: 3, ..Sha256::new() };
+        assert!(matches!(corrupt.try_finalize(), Err(ShaError::InvalidLength)));
+
+        let too_long = Sha256::from_state(H, u64::MAX - 63).unwrap();
+        assert!(matches!(too_long.try_finalize(), Err(ShaError::MessageTooLong)));
+    }
+
+    #[test]
+    fn sort_digests_test_sha256() {
+        let mut digests: Vec<Sha256Digest> = [b"a", b"b", b"c", b"d"].iter().map(|m| Sha256Digest(hash_bytes(*m))).collect();
+        sort_digests(&mut digests);
+
+        let hex: Vec<String> = digests.iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            hex,
+            [
+                "18ac3e7343f016890c510e93f935261169d9e3f565436429830faf0934f4f8e4", // d
+                "2e7d2c03a9507ae265ecf5b5356885a53393a2029d241394997265a1a25aefc6", // c
+                "3e23e8160039594a33894f6564e1b1348bbd7a0088d42c4acb73eeaed59c009d", // b
+                "ca978112ca1bbdcafac231b39a23dc4da786eff8147c4e72b9807785afee48bb", // a
+            ]
+        );
+    }
+
+    #[test]
+    fn set_hash_test_sha256() {
+        let (a, b) = (&b"apple"[..], &b"banana"[..]);
+        assert_eq!(set_hash(&[a, b]), set_hash(&[b, a]));
+        assert_ne!(set_hash(&[a, b]), set_hash(&[a, a]));
+        assert_ne!(set_hash(&[a, a]), set_hash(&[a]));
+        // not the same as plainly hashing the sorted digests
+        let mut joined = hash_bytes(a).to_vec();
+        joined.extend_from_slice(&hash_bytes(a));
+        assert_ne!(set_hash(&[a, a]), hash_bytes(&joined));
+    }
+
+    #[test]
+    fn empty_input_test_sha256() {
+        // an empty message still pads out to exactly one block
+        assert_eq!(message_padding(b"").len(), 64);
+        assert_eq!(hash_hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(Sha256::new().finalize(), hash(b""));
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn digest_hex_round_trip_test_sha256() {
+        let digest = Sha256Digest(state_to_bytes(&hash(b"hello world")));
+        let hex = digest.to_hex_string();
+        assert_eq!(hex, hash_hex(b"hello world"));
+        assert_eq!(Sha256Digest::from_hex(&hex).unwrap(), digest);
+        assert_eq!(Sha256Digest::from_hex(&hex.to_uppercase()).unwrap(), digest);
+        assert!(matches!(Sha256Digest::from_hex(&hex[..62]), Err(ShaError::InvalidHex)));
+    }
+
+    #[test]
+    fn digest_try_from_slice_test_sha256() {
+        let bytes = hash_bytes(b"hello world");
+        assert_eq!(Sha256Digest::try_from(&bytes[..]).unwrap(), Sha256Digest(bytes));
+        assert!(matches!(Sha256Digest::try_from(&bytes[..31]), Err(ShaError::InvalidLength)));
+        assert!(matches!(Sha256Digest::try_from(&[bytes.as_slice(), &[0]].concat()[..]), Err(ShaError::InvalidLength)));
+    }
+
+    #[test]
+    fn digest_as_bytes_test_sha256() {
+        fn byte_len(bytes: &[u8]) -> usize {
+            bytes.len()
+        }
+
+        let bytes = hash_bytes(b"hello world");
+        let digest = Sha256Digest(bytes);
+        assert_eq!(byte_len(digest.as_ref()), 32);
+        assert_eq!(digest.as_bytes(), &bytes);
+        assert_eq!(digest.into_bytes(), bytes);
+    }
+
+    #[test]
+    fn digest_iter_test_sha256() {
+        let bytes = hash_bytes(b"abc");
+        let digest = Sha256Digest(bytes);
+        assert_eq!(digest.iter().count(), 32);
+        assert_eq!(digest.iter().copied().collect::<Vec<u8>>(), bytes);
+        assert_eq!((&digest).into_iter().next(), Some(&0xba));
+
+        let mut collected = Vec::new();
+        for byte in digest {
+            collected.push(byte);
+        }
+        assert_eq!(collected, bytes);
+    }
+
+    #[test]
+    fn length_boundary_test_sha256() {
+        // 55 bytes + 0x80 + 8 length bytes fills one block exactly, 56 needs a second block
+        let cases = [
+            (55, 64, "9f4390f8d30c2dd92ec9f095b65e2b9ae9b0a925a5258e241c9f1e910f734318"),
+            (56, 128, "b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a"),
+            (57, 128, "f13b2d724659eb3bf47f2dd6af1accc87b81f09f59f2b75e5c0bed6589dfe8c6"),
+        ];
+        for (len, padded_len, expected) in cases {
+            let message = b"a".repeat(len);
+            assert_eq!(message_padding(&message).len(), padded_len);
+            assert_eq!(hash_hex(&message), expected);
+        }
+    }
+
+    #[test]
+    fn padded_len_test_sha256() {
+        for (len, padded) in [(0, 64), (55, 64), (56, 128), (63, 128), (64, 128)] {
+            assert_eq!(padded_len(len), padded);
+        }
+        for len in 0..300 {
+            assert_eq!(padded_len(len), message_padding(&vec![0; len]).len());
+        }
+    }
+
+    #[test]
+    fn exact_block_test_sha256() {
+        // 64 bytes (one block) pads to two blocks and 128 pads to three, the padding never fits in a full block
+        let cases = [
+            (64, 128, "ffe054fe7ae0cb6dc65c3af9b61d5209f439851db43d0ba5997337df154668eb"),
+            (128, 192, "6836cf13bac400e9105071cd6af47084dfacad4e5e302c94bfed24e013afb73e"),
+        ];
+        for (len, padded_len, expected) in cases {
+            let message = b"a".repeat(len);
+            assert_eq!(message_padding(&message).len(), padded_len);
+            assert_eq!(hash_hex(&message), expected);
+            assert_eq!(Sha256::new().chain(&message).finalize(), hash(&message));
+        }
+    }
+
+    #[test]
+    fn single_block_fast_path_test_sha256() {
+        // up to 55 bytes `hash` takes the single block path, after that the general one
+        for len in 0..58 {
+            let message = vec![0x61u8; len];
+            if len < 56 {
+                assert_eq!(single_block(&message)[..], message_padding(&message)[..]);
+            }
+            let mut hasher = Sha256::new();
+            hasher.update(&message);
+            assert_eq!(hash(&message), hasher.finalize());
+        }
+    }
+
+    #[test]
+    fn streaming_test_sha256() {
+        let message = b"a".repeat(1000);
+        let mut hasher = Sha256::new();
+        for piece in message.chunks(7) {
+            hasher.update(piece);
+        }
+        assert_eq!(hasher.finalize(), hash(&message));
+    }
+
+    #[test]
+    fn hash_vectored_test_sha256() {
+        assert_eq!(hash_vectored(&[b"hello ", b"world"]), hash(b"hello world"));
+    }
+
+    #[test]
+    fn hash_chunks_test_sha256() {
+        assert_eq!(hash_chunks(vec![&b"foo"[..], &b"bar"[..]]), hash(b"foobar"));
+        assert_eq!(hash_chunks("a,b,c".split(',').map(str::as_bytes)), hash(b"abc"));
+        assert_eq!(hash_chunks(std::iter::empty()), hash(b""));
+    }
+
+    #[test]
+    fn hash_iter_test_sha256() {
+        let bytes: Vec<u8> = (0u8..=255).cycle().take(1000).collect();
+        assert_eq!(hash_iter((0u8..=255).cycle().take(1000)), hash(&bytes));
+        assert_eq!(hash_iter(std::iter::empty()), hash(b""));
+    }
+
+    #[test]
+    fn hash_all_test_sha256() {
+        let digests = hash_all(&[b"a", b"bb", b""]);
+        assert_eq!(digests, [
+            [0xca978112, 0xca1bbdca, 0xfac231b3, 0x9a23dc4d, 0xa786eff8, 0x147c4e72, 0xb9807785, 0xafee48bb],
+            [0x3b64db95, 0xcb55c763, 0x391c7071, 0x08489ae1, 0x8b4112d7, 0x83300de3, 0x8e033b4c, 0x98c3deaf],
+            [0xe3b0c442, 0x98fc1c14, 0x9afbf4c8, 0x996fb924, 0x27ae41e4, 0x649b934c, 0xa495991b, 0x7852b855],
+        ]);
+        assert!(hash_all(&[]).is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_many_parallel_test_sha256() {
+        let owned: Vec<Vec<u8>> = (0..100).map(|i| vec![i as u8; i * 7]).collect();
+        let messages: Vec<&[u8]> = owned.iter().map(Vec::as_slice).collect();
+        let serial: Vec<[u8; 32]> = messages.iter().map(|message| hash_bytes(message)).collect();
+
+        assert_eq!(hash_many_parallel(&messages, 4), serial);
+        assert_eq!(hash_many_parallel(&messages, 0), serial);
+        assert_eq!(hash_many_parallel(&messages[..3], 8), serial[..3]);
+        assert!(hash_many_parallel(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn partial_blocks_test_sha256() {
+        // 3.5 blocks, fed in ways that hit both the buffered and the direct path
+        let message: Vec<u8> = (0..BLOCK_SIZE * 7 / 2).map(|i| i as u8).collect();
+        for split in [0, 1, BLOCK_SIZE / 2, BLOCK_SIZE, BLOCK_SIZE + 3, message.len()] {
+            let mut hasher = Sha256::new();
+            hasher.update(&message[..split]);
+            hasher.update(&message[split..]);
+            assert_eq!(hasher.finalize(), hash(&message));
+        }
+    }
+
+    #[test]
+    fn to_bytes_le_test_sha256() {
+        let hash = hash(b"hello world");
+        let be = state_to_bytes(&hash);
+        let le = state_to_bytes_le(&hash);
+        for (be_word, le_word) in be.chunks(4).zip(le.chunks(4)) {
+            let mut reversed = be_word.to_vec();
+            reversed.reverse();
+            assert_eq!(le_word, &reversed[..]);
+        }
+    }
+
+    #[test]
+    fn state_bytes_round_trip_test_sha256() {
+        let state = hash(b"hello world");
+        let bytes = hash_bytes(b"hello world");
+        assert_eq!(state_to_bytes(&state), bytes);
+        assert_eq!(bytes_to_state(&bytes), state);
+        assert_eq!(bytes_to_state(&state_to_bytes(&H)), H);
+    }
+
+    #[test]
+    fn finalize_into_test_sha256() {
+        let mut hasher = Sha256::new();
+        hasher.update(b"hello world");
+        let mut out = [0u8; 32];
+        hasher.clone().finalize_into(&mut out).unwrap();
+        assert_eq!(out, state_to_bytes(&hash(b"hello world")));
+
+        let mut short = [0u8; 31];
+        assert!(matches!(hasher.finalize_into(&mut short), Err(ShaError::InvalidLength)));
+    }
+
+    #[test]
+    fn finalize_verify_test_sha256() {
+        let mut expected = hash_bytes(b"hello world");
+        let mut hasher = Sha256::new();
+        hasher.update(b"hello world");
+        assert!(hasher.clone().finalize_verify(&expected));
+        assert!(!hasher.clone().finalize_verify(&expected[1..]));
+        expected[5] ^= 0x01;
+        assert!(!hasher.finalize_verify(&expected));
+    }
+
+    #[test]
+    fn digest_info_test_sha256() {
+        // RFC 8017 section 9.2, note 1
+        let prefix = [
+            0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04, 0x20,
+        ];
+        let digest = hash_bytes(b"abc");
+        let info = digest_info(&digest);
+        assert_eq!(&info[..19], &prefix[..]);
+        assert_eq!(&info[19..], &digest[..]);
+        assert_eq!(&DIGEST_INFO_PREFIX[4..4 + OID.len()], &OID[..]);
+    }
+
+    #[test]
+    fn finalize_reset_test_sha256() {
+        let mut hasher = Sha256::new();
+        hasher.update(b"hello ");
+        hasher.update(b"world");
+        assert_eq!(hasher.finalize_reset(), hash(b"hello world"));
+
+        hasher.update(b"abc");
+        assert_eq!(hasher.finalize_reset(), hash(b"abc"));
+    }
+
+    #[test]
+    fn digest_test_sha256() {
+        let mut hasher = Sha256::new();
+        hasher.update(b"ab");
+        assert_eq!(hasher.digest(), hash(b"ab"));
+
+        hasher.update(b"c");
+        assert_eq!(hasher.finalize(), hash(b"abc"));
+    }
+
+    #[test]
+    fn bytes_processed_test_sha256() {
+        let mut hasher = Sha256::new();
+        assert_eq!(hasher.bytes_processed(), 0);
+        hasher.update(&[0u8; 100]);
+        hasher.update(&[0u8; 50]);
+        assert_eq!(hasher.bytes_processed(), 150);
+    }
+
+    #[cfg(feature = "debug-dump")]
+    #[test]
+    fn debug_state_test_sha256() {
+        // less than a block, so the hash values are still the initial ones
+        let dump = Sha256::new().chain(b"abc").debug_state();
+        assert!(dump.starts_with("h0=6a09e667 "));
+        assert_eq!(dump.matches(" h").count() + 1, 8);
+        assert!(dump.ends_with("buffer=3/64 length=3"));
+    }
+
+    #[test]
+    fn finalize_with_len_test_sha256() {
+        let mut hasher = Sha256::new();
+        hasher.update(&[0x61; 100]);
+        hasher.update(&[0x61; 50]);
+        assert_eq!(hasher.finalize_with_len(), (hash_bytes(&[0x61; 150]), 150));
+    }
+
+    #[test]
+    fn chain_test_sha256() {
+        assert_eq!(Sha256::new().chain(b"a").chain(b"b").finalize(), hash(b"ab"));
+        assert_eq!(Sha256::new().chain(b"").finalize(), hash(b""));
+    }
+
+    #[test]
+    fn update_str_test_sha256() {
+        let mut hasher = Sha256::new();
+        for part in ["héllo", " ", "wörld", "", " ✓"] {
+            hasher.update_str(part);
+        }
+        assert_eq!(hasher.finalize(), hash("héllo wörld ✓".as_bytes()));
+    }
+
+    #[test]
+    fn update_len_prefixed_test_sha256() {
+        let fields = |a: &[u8], b: &[u8]| {
+            let mut hasher = Sha256::new();
+            hasher.update_len_prefixed(a);
+            hasher.update_len_prefixed(b);
+            hasher.finalize()
+        };
+        assert_ne!(fields(b"ab", b"c"), fields(b"a", b"bc"));
+        assert_eq!(fields(b"ab", b"c"), hash(b"\0\0\0\0\0\0\0\x02ab\0\0\0\0\0\0\0\x01c"));
+    }
+
+    #[test]
+    fn new_with_prefix_test_sha256() {
+        let mut manual = Sha256::new();
+        manual.update(b"prefix:");
+        manual.update(b"data");
+        let mut prefixed = Sha256::new_with_prefix(b"prefix:");
+        prefixed.update(b"data");
+        assert_eq!(prefixed.finalize(), manual.finalize());
+    }
+
+    #[test]
+    fn update_block_test_sha256() {
+        let message: Vec<u8> = (0..128).map(|i| i as u8).collect();
+        let mut hasher = Sha256::new();
+        hasher.update_block(message[..64].try_into().unwrap());
+        hasher.update_block(message[64..].try_into().unwrap());
+        assert_eq!(hasher.bytes_processed(), 128);
+        assert_eq!(hasher.finalize(), hash(&message));
+    }
+
+    #[test]
+    fn export_import_state_test_sha256() {
+        let message = "abc".repeat(100);
+        let (first, second) = message.as_bytes().split_at(150);
+
+        let mut hasher = Sha256::new();
+        hasher.update(first);
+        let state = hasher.export_state();
+        assert_eq!(state.length, 150);
+        assert_eq!(state.buffer_len, 150 % 64);
+
+        let mut resumed = Sha256::import_state(state).unwrap();
+        resumed.update(second);
+        assert_eq!(resumed.finalize(), hash(message.as_bytes()));
+
+        let bad = HasherState { buffer_len: 3, ..state };
+        assert!(matches!(Sha256::import_state(bad), Err(ShaError::InvalidLength)));
+    }
+
+    #[test]
+    fn large_length_test_sha256() {
+        // pretend 5 GiB were already hashed, then the final block is only padding and the length
+        let len: u64 = 5 << 30;
+        let hasher = Sha256::from_state(H, len).unwrap();
+
+        let mut block = [0u8; BLOCK_SIZE];
+        block[0] = 0x80;
+        // 5 GiB is 0xa_0000_0000 bits, too big for 32 bits
+        block[BLOCK_SIZE - 8..].copy_from_slice(&[0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x00]);
+        let mut expected = H;
+        compress(&mut expected, &block);
+
+        assert_eq!(hasher.finalize(), expected);
+    }
+
+    #[test]
+    fn tagged_hash_test_sha256() {
+        // SHA256("BIP0340/challenge") is the well known challenge tag midstate prefix
+        assert_eq!(hash_hex(b"BIP0340/challenge"), "7bb52d7a9fef58323eb1bf7a407db382d2f3f2d81bb1224f49fe518f6d48d37c");
+        assert_eq!(tagged_hash(b"BIP0340/challenge", b"hello world"), [
+            0x85, 0x60, 0x98, 0x3a, 0x12, 0x46, 0xfd, 0xa5, 0x0f, 0xb2, 0xf1, 0xba, 0x7b, 0x68, 0x2f, 0x4f,
+            0x39, 0x4c, 0x1f, 0x3d, 0xc9, 0xfb, 0xb2, 0x1a, 0x36, 0x0c, 0x66, 0x06, 0xd4, 0x11, 0xf7, 0x2d
+        ]);
+    }
+
+    #[test]
+    fn midstate_test_sha256() {
+        let prefix = b"x".repeat(128);
+        let mut hasher = Sha256::new();
+        hasher.update(&prefix);
+        let (state, len) = hasher.midstate().unwrap();
+        assert_eq!(len, 128);
+
+        for suffix in [&b""[..], b"a", b"hello world", &[0u8; 100]] {
+            let mut resumed = Sha256::from_state(state, len).unwrap();
+            resumed.update(suffix);
+            assert_eq!(resumed.finalize(), hash(&[&prefix[..], suffix].concat()));
+        }
+
+        hasher.update(b"not a full block");
+        assert_eq!(hasher.midstate(), None);
+    }
+
+    #[test]
+    fn from_state_length_test_sha256() {
+        assert!(Sha256::from_state(H, 0).is_ok());
+        assert!(Sha256::from_state(H, 128).is_ok());
+        assert!(matches!(Sha256::from_state(H, 100), Err(ShaError::InvalidLength)));
+        assert!(matches!(Sha256::from_state(H, 1), Err(ShaError::InvalidLength)));
+    }
+
+    #[test]
+    fn double_sha256_test() {
+        // bitcoin genesis block header
+        let header_hex = "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c";
+        let header: Vec<u8> = (0..header_hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&header_hex[i..i + 2], 16).unwrap())
+            .collect();
+
+        // block ids are displayed byte reversed, so this is 000000000019d6689c...
+        let mut block_id = double_sha256(&header);
+        block_id.reverse();
+        let block_id: String = block_id.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(block_id, "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f");
+    }
+
+    #[test]
+    fn compose_256_512_test() {
+        assert_eq!(
+            encode_hex(&compose_256_512(b"hello world")),
+            "e78fba29fc736d08b31392fc5316c51afda6b0e77b88857743789569283eb5e4"
+        );
+    }
+
+    #[test]
+    fn keyed_hash_test_sha256() {
+        assert_eq!(keyed_hash(b"key", b"message"), hash_bytes(b"\0\0\0\0\0\0\0\x03keymessage"));
+        assert_ne!(keyed_hash(b"key1", b"message"), keyed_hash(b"key2", b"message"));
+        // same concatenation, different split
+        assert_ne!(keyed_hash(b"key", b"message"), keyed_hash(b"keym", b"essage"));
+    }
+
+    #[test]
+    fn salted_hash_test_sha256() {
+        assert_eq!(salted_hash(b"salt", b"password"), hash_bytes(b"\0\0\0\0\0\0\0\x04saltpassword"));
+        // moving a byte across the boundary, either way
+        assert_ne!(salted_hash(b"salt", b"password"), salted_hash(b"saltp", b"assword"));
+        assert_ne!(salted_hash(b"salt", b"password"), salted_hash(b"sal", b"tpassword"));
+    }
+
+    #[test]
+    fn hash_range_test_sha256() {
+        let buf = b"hello world";
+        assert_eq!(hash_range(buf, 6..11).unwrap(), hash_bytes(b"world"));
+        assert_eq!(hash_range(buf, 3..3).unwrap(), hash_bytes(b""));
+        assert!(matches!(hash_range(buf, 6..12), Err(ShaError::InvalidLength)));
+        #[allow(clippy::reversed_empty_ranges)]
+        let backwards = 5..2;
+        assert!(matches!(hash_range(buf, backwards), Err(ShaError::InvalidLength)));
+    }
+
+    #[test]
+    fn concat_hash_test() {
+        assert_ne!(concat_hash(&[b"ab", b"c"]), concat_hash(&[b"a", b"bc"]));
+        assert_ne!(concat_hash(&[b"a", b"b"]), concat_hash(&[b"b", b"a"]));
+        assert_ne!(concat_hash(&[b"abc"]), concat_hash(&[b"abc", b""]));
+
+        let expected = state_to_bytes(&hash(&[&1u64.to_be_bytes()[..], b"a", &2u64.to_be_bytes(), b"bc"].concat()));
+        assert_eq!(concat_hash(&[b"a", b"bc"]), expected);
+    }
+
+    #[test]
+    fn truncated_test_sha256() {
+        let full = state_to_bytes(&hash(b"hello world"));
+        assert_eq!(truncated::<0>(b"hello world"), [0u8; 0]);
+        assert_eq!(truncated::<4>(b"hello world"), [0xb9, 0x4d, 0x27, 0xb9]);
+        assert_eq!(truncated::<16>(b"hello world"), full[..16]);
+        assert_eq!(truncated::<32>(b"hello world"), full);
+    }
+
+    #[test]
+    fn fingerprint64_test_sha256() {
+        let full = hash_bytes(b"abc");
+        assert_eq!(fingerprint64(b"abc"), u64::from_be_bytes(full[..8].try_into().unwrap()));
+        assert_eq!(fingerprint64(b"abc"), 0xba7816bf8f01cfea);
+    }
+
+    #[test]
+    fn sha_hasher_test() {
+        use std::hash::Hasher;
+
+        let mut first = ShaHasher::new();
+        first.write(b"hello ");
+        first.write(b"world");
+        let mut second = ShaHasher::new();
+        second.write(b"hello world");
+
+        assert_eq!(first.finish(), second.finish());
+        assert_eq!(first.finish(), 0xb94d27b9934d3e08);
+        assert_eq!(first.digest(), hash(b"hello world"));
+    }
+
+    #[test]
+    fn sha_hasher_finish_full_test() {
+        use std::hash::Hasher;
+
+        fn write_all<H: Hasher>(hasher: &mut H) {
+            hasher.write(b"hello ");
+            hasher.write_u8(b'w');
+            hasher.write(b"orld");
+        }
+
+        let mut hasher = ShaHasher::new();
+        write_all(&mut hasher);
+        let full = hasher.finish_full();
+        assert_eq!(full, hash_bytes(b"hello world"));
+        assert_eq!(hasher.finish(), u64::from_be_bytes(full[..8].try_into().unwrap()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn tee_hasher_test_sha256() {
+        use std::io::Write;
+
+        let message = "abc".repeat(100);
+        let mut tee = TeeHasher::new(Vec::new());
+        for piece in message.as_bytes().chunks(7) {
+            tee.write_all(piece).unwrap();
+        }
+        let (written, digest) = tee.finish();
+        assert_eq!(written, message.as_bytes());
+        assert_eq!(digest, hash_bytes(message.as_bytes()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hashing_writer_test_sha256() {
+        use std::io::Write;
+
+        let mut writer = HashingWriter::new(Vec::new());
+        let mut log = String::new();
+        for line in ["started\n", "request 1\n", "request 2\n", "stopped\n"] {
+            writeln!(writer, "{}", line.trim_end()).unwrap();
+            log.push_str(line);
+            assert_eq!(writer.digest(), hash_bytes(log.as_bytes()));
+        }
+        assert_eq!(writer.into_inner(), log.as_bytes());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_reader_with_progress_test_sha256() {
+        let message = vec![0x61u8; 20000];
+        let mut progress = Vec::new();
+        let digest = hash_reader_with_progress(&mut std::io::Cursor::new(&message), |read| progress.push(read)).unwrap();
+
+        assert_eq!(digest, hash_bytes(&message));
+        assert_eq!(progress, [8192, 16384, 20000, 20000]);
+
+        progress.clear();
+        let digest = hash_reader_with_progress(&mut std::io::empty(), |read| progress.push(read)).unwrap();
+        assert_eq!(digest, hash_bytes(b""));
+        assert_eq!(progress, [0]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_reader_error_test_sha256() {
+        struct Broken;
+
+        impl std::io::Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("broken"))
+            }
+        }
+
+        let err = hash_reader_with_progress(&mut Broken, |_| {}).unwrap_err();
+        assert!(matches!(err, ShaError::Io(_)));
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn hash_gzip_decompressed_test_sha256() {
+        use std::io::Write;
+
+        let original = "hello world\n".repeat(1000);
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(original.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(hash_gzip_decompressed(&compressed[..]).unwrap(), hash_bytes(original.as_bytes()));
+        let truncated = &compressed[..compressed.len() / 2];
+        assert!(matches!(hash_gzip_decompressed(truncated), Err(ShaError::Io(_))));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn hash_canonical_json_test_sha256() {
+        let a: serde_json::Value =
+            serde_json::from_str(r#"{"b": 1, "a": {"y": [1, {"q": true, "p": null}], "x": "s"}}"#).unwrap();
+        let b: serde_json::Value =
+            serde_json::from_str(r#"{"a":{"x":"s","y":[1,{"p":null,"q":true}]},"b":1}"#).unwrap();
+        assert_eq!(hash_canonical_json(&a), hash_canonical_json(&b));
+        assert_eq!(
+            hash_canonical_json(&a),
+            hash_bytes(br#"{"a":{"x":"s","y":[1,{"p":null,"q":true}]},"b":1}"#)
+        );
+
+        // array order is data, so it does count
+        let c = serde_json::json!({"a": {"x": "s", "y": [{"p": null, "q": true}, 1]}, "b": 1});
+        assert_ne!(hash_canonical_json(&a), hash_canonical_json(&c));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn hash_async_reader_test_sha256() {
+        use std::pin::Pin;
+        use std::task::{Context, Poll};
+
+        // hands out 3 bytes at a time, and is only ready every other poll
+        struct Trickle<'a> {
+            data: &'a [u8],
+            ready: bool,
+        }
+
+        impl tokio::io::AsyncRead for Trickle<'_> {
+            fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut tokio::io::ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+                self.ready = !self.ready;
+                if !self.ready {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                let take = self.data.len().min(3).min(buf.remaining());
+                buf.put_slice(&self.data[..take]);
+                self.data = &self.data[take..];
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        let message = "abc".repeat(1000);
+        let mut reader = Trickle { data: message.as_bytes(), ready: false };
+        assert_eq!(hash_async_reader(&mut reader).await.unwrap(), hash_bytes(message.as_bytes()));
+        assert_eq!(hash_async_reader(&mut &b""[..]).await.unwrap(), hash_bytes(b""));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn hash_async_reader_interrupted_test_sha256() {
+        use std::pin::Pin;
+        use std::task::{Context, Poll};
+
+        // fails with `Interrupted` once, then gives the data
+        struct InterruptedOnce<'a> {
+            data: &'a [u8],
+            interrupted: bool,
+        }
+
+        impl tokio::io::AsyncRead for InterruptedOnce<'_> {
+            fn poll_read(mut self: Pin<&mut Self>, _: &mut Context<'_>, buf: &mut tokio::io::ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+                if !self.interrupted {
+                    self.interrupted = true;
+                    return Poll::Ready(Err(std::io::ErrorKind::Interrupted.into()));
+                }
+                let take = self.data.len().min(buf.remaining());
+                buf.put_slice(&self.data[..take]);
+                self.data = &self.data[take..];
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        let mut reader = InterruptedOnce { data: b"hello world", interrupted: false };
+        assert_eq!(hash_async_reader(&mut reader).await.unwrap(), hash_bytes(b"hello world"));
+        assert!(reader.interrupted);
+    }
 }
 
 
@@ -1,11 +1,23 @@
 // sha512.rs contains code from https://csrc.nist.gov/files/pubs/fips/180-2/final/docs/fips180-2.pdf
+use crate::block::{blocks, remainder};
+use crate::digest::{digest_type, encode_hex, Digest};
+use crate::error::ShaError;
+
+/// How long `message_padding` makes a message of `message_len` bytes, without building it
+/// That's the message, the 0x80 byte and the 16 length bytes, rounded up to whole blocks
+pub fn padded_len(message_len: usize) -> usize {
+    (message_len + 17).div_ceil(BLOCK_SIZE) * BLOCK_SIZE
+}
 
 /// Following the standard, the message is to be padded as follows:
 /// 1. Append a 1 bit to the message
 /// 2. Append 0 bits until the length of the message is congruent to 896 mod 1024
 /// 3. Append the length of the message in bits as a 128 bit number
+///
+/// A message that is exactly one block (128 bytes) becomes two blocks, and 256 bytes becomes three
 pub fn message_padding(message: &[u8]) -> Vec<u8> {
-    let message_len_bits = (message.len() * 8) as u128;
+    // widen before multiplying, a usize can overflow on 32 bit targets
+    let message_len_bits = message.len() as u128 * 8;
     let mut message_bytes = Vec::from(message);
 
     message_bytes.push(0x80);
@@ -13,7 +25,7 @@ pub fn message_padding(message: &[u8]) -> Vec<u8> {
     // Calculate padding length for 1024-bit block size
     // The total length (message + padding + length field) should be a multiple of 1024 bits
     let padding_len = (128 - (message_bytes.len() + 16) % 128) % 128;
-    message_bytes.extend(std::iter::repeat(0).take(padding_len));
+    message_bytes.extend(std::iter::repeat_n(0, padding_len));
 
     message_bytes.extend_from_slice(&message_len_bits.to_be_bytes());
 
@@ -54,32 +66,54 @@ pub const K: [u64; 80] = [
     0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817
 ];
 
+/// Size of a SHA-512 message block in bytes
+pub const BLOCK_SIZE: usize = 128;
+
+/// Size of a SHA-512 digest in bytes
+pub const OUTPUT_SIZE: usize = 64;
+
+/// DER encoding of the SHA-512 object identifier (2.16.840.1.101.3.4.2.3), tag and length included
+pub const OID: [u8; 11] = [0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03];
+
+/// What goes in front of the digest in a DER `DigestInfo` (RFC 8017 section 9.2 note 1):
+/// the algorithm identifier with NULL parameters, then the header of the digest octet string
+pub const DIGEST_INFO_PREFIX: [u8; 19] = [
+    0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03, 0x05, 0x00, 0x04, 0x40,
+];
+
 // functions
+#[inline]
 pub fn ch(x: u64, y: u64, z: u64) -> u64 {
     (x & y) ^ ((!x) & z)
 }
 
+#[inline]
 pub fn maj(x: u64, y: u64, z: u64) -> u64 {
     (x & y) ^ (x & z) ^ (y & z)
 }
 
+#[inline]
 pub fn big_sigma_0(x: u64) -> u64 {
     x.rotate_right(28) ^ x.rotate_right(34) ^ x.rotate_right(39)
 }
 
+#[inline]
 pub fn big_sigma_1(x: u64) -> u64 {
     x.rotate_right(14) ^ x.rotate_right(18) ^ x.rotate_right(41)
 }
 
+#[inline]
 pub fn small_sigma_0(x: u64) -> u64 {
     x.rotate_right(1) ^ x.rotate_right(8) ^ (x >> 7)
 }
 
+#[inline]
 pub fn small_sigma_1(x: u64) -> u64 {
     x.rotate_right(19) ^ x.rotate_right(61) ^ (x >> 6)
 }
 
 /// The hash function for SHA-512
+/// This returns the 8 hash values as words, use `hash_bytes` to get the standard 64 byte digest instead
 /// 
 /// ```
 /// use sha::sha512::hash;
@@ -93,75 +127,384 @@ pub fn small_sigma_1(x: u64) -> u64 {
 /// println!();
 /// ```
 pub fn hash(message: &[u8]) -> [u64; 8] {
+    // short messages pad out to a single block, so skip building the padded copy
+    if message.len() < BLOCK_SIZE - 16 {
+        let mut h_const = H;
+        compress(&mut h_const, &single_block(message));
+        return h_const;
+    }
+
     let message_bytes = message_padding(message);
     let mut h_const = H;
 
-    for chunk in message_bytes.chunks(128) {
-        let mut w = [0u64; 80];
-
-        for t in 0..16 {
-            w[t] = u64::from_be_bytes([
-                chunk[t * 8],
-                chunk[t * 8 + 1],
-                chunk[t * 8 + 2],
-                chunk[t * 8 + 3],
-                chunk[t * 8 + 4],
-                chunk[t * 8 + 5],
-                chunk[t * 8 + 6],
-                chunk[t * 8 + 7],
-            ]);
-        }
-
-        for i in 16..80 {
-            let s0 = small_sigma_0(w[i - 15]);
-            let s1 = small_sigma_1(w[i - 2]);
-            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
-        }
-
-        let mut a = h_const[0];
-        let mut b = h_const[1];
-        let mut c = h_const[2];
-        let mut d = h_const[3];
-        let mut e = h_const[4];
-        let mut f = h_const[5];
-        let mut g = h_const[6];
-        let mut h = h_const[7];
-
-        for i in 0..80 {
-            let s1 = big_sigma_1(e);
-            let ch = ch(e, f, g);
-            let temp1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
-            let s0 = big_sigma_0(a);
-            let maj = maj(a, b, c);
-            let temp2 = s0.wrapping_add(maj);
-
-            h = g;
-            g = f;
-            f = e;
-            e = d.wrapping_add(temp1);
-            d = c;
-            c = b;
-            b = a;
-            a = temp1.wrapping_add(temp2);
-        }
-
-        h_const[0] = h_const[0].wrapping_add(a);
-        h_const[1] = h_const[1].wrapping_add(b);
-        h_const[2] = h_const[2].wrapping_add(c);
-        h_const[3] = h_const[3].wrapping_add(d);
-        h_const[4] = h_const[4].wrapping_add(e);
-        h_const[5] = h_const[5].wrapping_add(f);
-        h_const[6] = h_const[6].wrapping_add(g);
-        h_const[7] = h_const[7].wrapping_add(h);
+    for chunk in blocks(&message_bytes) {
+        compress(&mut h_const, chunk);
     }
 
     h_const
 }
 
+/// The padded form of a message shorter than `BLOCK_SIZE - 16`, which fits in one block
+/// Same layout as `message_padding`, just built on the stack
+fn single_block(message: &[u8]) -> [u8; BLOCK_SIZE] {
+    let mut block = [0u8; BLOCK_SIZE];
+    block[..message.len()].copy_from_slice(message);
+    block[message.len()] = 0x80;
+    block[BLOCK_SIZE - 16..].copy_from_slice(&((message.len() as u128) * 8).to_be_bytes());
+    block
+}
+
+/// Process a single 128 byte chunk of the padded message, updating the hash values in place
+fn compress(h_const: &mut [u64; 8], chunk: &[u8; BLOCK_SIZE]) {
+    let mut w = [0u64; 80];
+
+    // the chunk is 16 big-endian words, the zip stops after those
+    for (w_t, word) in w.iter_mut().zip(chunk.chunks_exact(8)) {
+        *w_t = u64::from_be_bytes(word.try_into().unwrap());
+    }
+
+    for i in 16..80 {
+        let s0 = small_sigma_0(w[i - 15]);
+        let s1 = small_sigma_1(w[i - 2]);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+
+    let mut a = h_const[0];
+    let mut b = h_const[1];
+    let mut c = h_const[2];
+    let mut d = h_const[3];
+    let mut e = h_const[4];
+    let mut f = h_const[5];
+    let mut g = h_const[6];
+    let mut h = h_const[7];
+
+    for i in 0..80 {
+        let s1 = big_sigma_1(e);
+        let ch = ch(e, f, g);
+        let temp1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+        let s0 = big_sigma_0(a);
+        let maj = maj(a, b, c);
+        let temp2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+
+    h_const[0] = h_const[0].wrapping_add(a);
+    h_const[1] = h_const[1].wrapping_add(b);
+    h_const[2] = h_const[2].wrapping_add(c);
+    h_const[3] = h_const[3].wrapping_add(d);
+    h_const[4] = h_const[4].wrapping_add(e);
+    h_const[5] = h_const[5].wrapping_add(f);
+    h_const[6] = h_const[6].wrapping_add(g);
+    h_const[7] = h_const[7].wrapping_add(h);
+}
+
+digest_type!(
+    /// A SHA-512 digest as its 64 standard (big-endian) bytes
+    Sha512Digest, 64
+);
+
+/// Convert the hash values into the standard SHA-512 digest bytes (each word big-endian)
+pub fn state_to_bytes(state: &[u64; 8]) -> [u8; 64] {
+    let mut bytes = [0u8; 64];
+    for (chunk, h) in bytes.chunks_exact_mut(8).zip(state.iter()) {
+        chunk.copy_from_slice(&h.to_be_bytes());
+    }
+    bytes
+}
+
+/// Convert digest bytes back into the hash values, the opposite of `state_to_bytes`
+pub fn bytes_to_state(bytes: &[u8; 64]) -> [u64; 8] {
+    let mut state = [0u64; 8];
+    for (h, chunk) in state.iter_mut().zip(bytes.chunks_exact(8)) {
+        *h = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    state
+}
+
+/// Same as `state_to_bytes` but with each word in little-endian order
+/// Big-endian is what the standard uses and what other tools print, so only use this
+/// for formats that store the words the other way around
+pub fn state_to_bytes_le(state: &[u64; 8]) -> [u8; 64] {
+    let mut bytes = [0u8; 64];
+    for (chunk, h) in bytes.chunks_exact_mut(8).zip(state.iter()) {
+        chunk.copy_from_slice(&h.to_le_bytes());
+    }
+    bytes
+}
+
+/// Hash a message and return the 64 digest bytes instead of the hash words
+pub fn hash_bytes(message: &[u8]) -> [u8; 64] {
+    state_to_bytes(&hash(message))
+}
+
+/// The DER `DigestInfo` for a SHA-512 digest, as signed in PKCS#1 v1.5 signatures
+/// Panics if `digest` isn't `OUTPUT_SIZE` bytes, the prefix encodes that length
+pub fn digest_info(digest: &[u8]) -> Vec<u8> {
+    assert_eq!(digest.len(), OUTPUT_SIZE, "not a SHA-512 digest");
+    [&DIGEST_INFO_PREFIX[..], digest].concat()
+}
+
+/// Streaming SHA-512 hasher, for when the message isn't available all at once
+/// Feeding the message in pieces gives the same result as `hash` on the whole message.
+/// All of its state is in fixed size arrays, so `update` and `finalize` never allocate
+/// ```
+/// use sha::sha512::{hash, Sha512};
+///
+/// let mut hasher = Sha512::new();
+/// hasher.update(b"hello ");
+/// hasher.update(b"world");
+/// assert_eq!(hasher.finalize(), hash(b"hello world"));
+/// ```
+///
+/// `finalize` takes the hasher by value, `finalize_reset` leaves a fresh hasher and `digest` works on a copy,
+/// so there's never a finished hasher around to be misused
+#[derive(Clone, Debug)]
+pub struct Sha512 {
+    h: [u64; 8],
+    buffer: [u8; BLOCK_SIZE],
+    buffer_len: usize,
+    length: u128,
+}
+
+impl Sha512 {
+    /// Create a new hasher starting from the initial hash values
+    pub fn new() -> Self {
+        Self {
+            h: H,
+            buffer: [0; BLOCK_SIZE],
+            buffer_len: 0,
+            length: 0,
+        }
+    }
+
+    /// Add more of the message, anything that doesn't fill a whole block is kept until the next call
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        self.length = self.length.wrapping_add(data.len() as u128);
+
+        // top up a partly filled buffer first
+        if self.buffer_len > 0 {
+            let take = (BLOCK_SIZE - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+
+            if self.buffer_len < BLOCK_SIZE {
+                return;
+            }
+            compress(&mut self.h, &self.buffer);
+            self.buffer_len = 0;
+        }
+
+        // whole blocks can be compressed straight from the input, no need to copy them into the buffer
+        for block in blocks(data) {
+            compress(&mut self.h, block);
+        }
+
+        let rest = remainder::<BLOCK_SIZE>(data);
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
+    }
+
+    /// A hasher that has already absorbed `prefix`, for protocols that hash a fixed prefix before the data
+    /// To skip re-hashing a long prefix every time, make this once and `clone` it per message
+    pub fn new_with_prefix(prefix: &[u8]) -> Self {
+        let mut hasher = Self::new();
+        hasher.update(prefix);
+        hasher
+    }
+
+    /// `update` that takes and returns the hasher, for writing it all in one expression
+    /// ```
+    /// use sha::sha512::{hash, Sha512};
+    ///
+    /// assert_eq!(Sha512::new().chain(b"a").chain(b"b").finalize(), hash(b"ab"));
+    /// ```
+    pub fn chain(mut self, data: &[u8]) -> Self {
+        self.update(data);
+        self
+    }
+
+    /// Add a string to the message, this hashes its UTF-8 encoding, same as `update(s.as_bytes())`
+    pub fn update_str(&mut self, s: &str) {
+        self.update(s.as_bytes());
+    }
+
+    /// Add `data` as one field: its length as 8 bytes big-endian, then the bytes
+    /// Fields written this way can't run into each other, `("ab", "c")` and `("a", "bc")` hash differently
+    pub fn update_len_prefixed(&mut self, data: &[u8]) {
+        self.update(&(data.len() as u64).to_be_bytes());
+        self.update(data);
+    }
+
+    /// Pad whatever is left in the buffer (same as `message_padding`) and return the hash
+    pub fn finalize(mut self) -> [u64; 8] {
+        let message_len_bits = self.length.wrapping_mul(8);
+
+        self.buffer[self.buffer_len] = 0x80;
+        self.buffer[self.buffer_len + 1..].fill(0);
+
+        // not enough room left for the length, so it goes in an extra block
+        if self.buffer_len >= BLOCK_SIZE - 16 {
+            compress(&mut self.h, &self.buffer);
+            self.buffer = [0; BLOCK_SIZE];
+        }
+
+        self.buffer[BLOCK_SIZE - 16..].copy_from_slice(&message_len_bits.to_be_bytes());
+        compress(&mut self.h, &self.buffer);
+
+        self.h
+    }
+
+    /// Finish the hash and return the digest bytes along with the total number of bytes hashed
+    pub fn finalize_with_len(self) -> ([u8; 64], u128) {
+        let len = self.length;
+        (state_to_bytes(&self.finalize()), len)
+    }
+
+    /// How many bytes have been added so far, across all the `update` calls.
+    /// This is a `u128` to match the 128 bit length field SHA-512 uses
+    pub fn bytes_processed(&self) -> u128 {
+        self.length
+    }
+
+    /// The hash of everything added so far, without finishing the hasher
+    /// The padding is done on a copy, so more data can still be added afterwards
+    pub fn digest(&self) -> [u64; 8] {
+        self.clone().finalize()
+    }
+
+    /// Finish the hash and reset the hasher back to the initial hash values, ready for the next message
+    pub fn finalize_reset(&mut self) -> [u64; 8] {
+        std::mem::take(self).finalize()
+    }
+
+    /// Finish the hash and write the digest bytes (big-endian, same as `state_to_bytes`) into `out`
+    /// Fails if `out` is shorter than `OUTPUT_SIZE`, any bytes after the digest are left alone
+    pub fn finalize_into(self, out: &mut [u8]) -> Result<(), ShaError> {
+        if out.len() < OUTPUT_SIZE {
+            return Err(ShaError::InvalidLength);
+        }
+        out[..OUTPUT_SIZE].copy_from_slice(&state_to_bytes(&self.finalize()));
+        Ok(())
+    }
+
+    /// Finish the hash and check it against `expected` digest bytes, in constant time
+    /// A wrong length is just a mismatch
+    pub fn finalize_verify(self, expected: &[u8]) -> bool {
+        crate::ct::eq(&state_to_bytes(&self.finalize()), expected)
+    }
+}
+
+impl Default for Sha512 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Digest for Sha512 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+    const OUTPUT_SIZE: usize = OUTPUT_SIZE;
+    type Output = [u8; 64];
+
+    fn new() -> Self {
+        Sha512::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha512::update(self, data);
+    }
+
+    fn finalize_bytes(self) -> Self::Output {
+        state_to_bytes(&self.finalize())
+    }
+}
+
+/// Hash a message made up of several parts without joining them together first
+/// The result is the same as hashing the concatenation of `parts`
+pub fn hash_vectored(parts: &[&[u8]]) -> [u64; 8] {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize()
+}
+
+/// The first `N` bytes of the digest, for short ids and display hashes
+/// `N` can be anything from 0 up to `OUTPUT_SIZE`, this is checked at compile time.
+/// Keep in mind a truncated hash is only as collision resistant as its length
+pub fn truncated<const N: usize>(message: &[u8]) -> [u8; N] {
+    const { assert!(N <= OUTPUT_SIZE, "can't truncate a digest to more than OUTPUT_SIZE bytes") };
+
+    let mut out = [0u8; N];
+    out.copy_from_slice(&hash_bytes(message)[..N]);
+    out
+}
+
+/// Hash bytes from an iterator (a decompressor or some other generator) without collecting them first
+/// The bytes are gathered a block at a time before being passed to the hasher
+pub fn hash_iter<I: IntoIterator<Item = u8>>(iter: I) -> [u64; 8] {
+    let mut hasher = Sha512::new();
+    let mut block = [0u8; BLOCK_SIZE];
+    let mut block_len = 0;
+
+    for byte in iter {
+        block[block_len] = byte;
+        block_len += 1;
+        if block_len == BLOCK_SIZE {
+            hasher.update(&block);
+            block_len = 0;
+        }
+    }
+
+    hasher.update(&block[..block_len]);
+    hasher.finalize()
+}
+
+/// Hash each message separately, the results are in the same order as `messages`
+pub fn hash_all(messages: &[&[u8]]) -> Vec<[u64; 8]> {
+    messages.iter().map(|message| hash(message)).collect()
+}
+
+/// Format a digest the way `openssl dgst -sha512` prints it: `SHA512(<label>)= <hex>`
+/// `label` is whatever was hashed, usually a file name or `stdin`.
+/// This is the OpenSSL 1.x style, which is what most existing scripts parse
+pub fn openssl_format(label: &str, digest: &[u8]) -> String {
+    format!("SHA512({})= {}", label, encode_hex(digest))
+}
+
+/// Hash a string slice with SHA-512
+/// Note that this hashes the UTF-8 encoding of the string, which is what
+/// other implementations do when given text, so the result is the same as `hash(s.as_bytes())`
+pub fn hash_str(s: &str) -> [u64; 8] {
+    hash(s.as_bytes())
+}
+
+/// Hash a message with SHA-512 and return the digest as a lowercase hex string
+/// Every word is zero padded, so the string is always 128 characters long
+/// ```
+/// use sha::sha512::hash_hex;
+///
+/// println!("{}", hash_hex(b"hello world"));
+/// ```
+pub fn hash_hex(message: &[u8]) -> String {
+    hash(message).iter().map(|h| format!("{:016x}", h)).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const _: () = assert!(BLOCK_SIZE == 128 && OUTPUT_SIZE == 64);
+
     #[test]
     fn test_message_padding() {
         let message = b"hello world";
@@ -197,4 +540,276 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_hash_str_sha512() {
+        assert_eq!(hash_str("abc"), hash(b"abc"));
+    }
+
+    #[test]
+    fn test_hash_hex_sha512() {
+        assert_eq!(hash_hex(b"hello world"), "309ecc489c12d6eb4cc40f50c902f2b4d0ed77ee511a7c7a9bcd3ca86d4cd86f989dd35bc5ff499670da34255b45b0cfd830e81f605dcf7dc5542e93ae9cd76f");
+    }
+
+    #[test]
+    fn test_empty_input_sha512() {
+        // an empty message still pads out to exactly one block
+        assert_eq!(message_padding(b"").len(), 128);
+        assert_eq!(hash_hex(b""), "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e");
+        assert_eq!(Sha512::new().finalize(), hash(b""));
+    }
+
+    #[test]
+    fn test_length_boundary_sha512() {
+        // the 128 bit length field moves the boundary to 111/112 bytes for SHA-512
+        let cases = [
+            (55, 128, "b0220c772cbf6c1822e2cb38a437d0e1d58772417a4bbb21c961364f8b6143e05aa6316dca8d1d7b19e16448419076395f6086cb55101fbd6d5497b148e1745f"),
+            (56, 128, "962b64aae357d2a4fee3ded8b539bdc9d325081822b0bfc55583133aab44f18bafe11d72a7ae16c79ce2ba620ae2242d5144809161945f1367f41b3972e26e04"),
+            (57, 128, "d3115798e872fc1ca6b276368e8ea0926daec6ab1f8f08297e4348ff5f5fe4c6e5205413271babafd4929b070754bc5800e5db44790666ec4e2f6ac52a17e163"),
+            (111, 128, "fa9121c7b32b9e01733d034cfc78cbf67f926c7ed83e82200ef86818196921760b4beff48404df811b953828274461673c68d04e297b0eb7b2b4d60fc6b566a2"),
+            (112, 256, "c01d080efd492776a1c43bd23dd99d0a2e626d481e16782e75d54c2503b5dc32bd05f0f1ba33e568b88fd2d970929b719ecbb152f58f130a407c8830604b70ca"),
+            (113, 256, "55ddd8ac210a6e18ba1ee055af84c966e0dbff091c43580ae1be703bdb85da31acf6948cf5bd90c55a20e5450f22fb89bd8d0085e39f85a86cc46abbca75e24d"),
+        ];
+        for (len, padded_len, expected) in cases {
+            let message = b"a".repeat(len);
+            assert_eq!(message_padding(&message).len(), padded_len);
+            assert_eq!(hash_hex(&message), expected);
+        }
+    }
+
+    #[test]
+    fn test_padded_len_sha512() {
+        for (len, padded) in [(0, 128), (111, 128), (112, 256), (127, 256), (128, 256)] {
+            assert_eq!(padded_len(len), padded);
+        }
+        for len in 0..300 {
+            assert_eq!(padded_len(len), message_padding(&vec![0; len]).len());
+        }
+    }
+
+    #[test]
+    fn test_exact_block_sha512() {
+        // 128 bytes (one block) pads to two blocks and 256 pads to three, the padding never fits in a full block
+        let cases = [
+            (128, 256, "b73d1929aa615934e61a871596b3f3b33359f42b8175602e89f7e06e5f658a243667807ed300314b95cacdd579f3e33abdfbe351909519a846d465c59582f321"),
+            (256, 384, "6a9169eb662f136d87374070e8828b3e615a7eca32a89446e9225b02832709be095e635c824a2bb70213ba2ea0ababac0809827843992c851903b7ac0c136699"),
+        ];
+        for (len, padded_len, expected) in cases {
+            let message = b"a".repeat(len);
+            assert_eq!(message_padding(&message).len(), padded_len);
+            assert_eq!(hash_hex(&message), expected);
+            assert_eq!(Sha512::new().chain(&message).finalize(), hash(&message));
+        }
+    }
+
+    #[test]
+    fn test_single_block_fast_path_sha512() {
+        // up to 111 bytes `hash` takes the single block path, after that the general one
+        for len in 0..114 {
+            let message = vec![0x61u8; len];
+            if len < 112 {
+                assert_eq!(single_block(&message)[..], message_padding(&message)[..]);
+            }
+            let mut hasher = Sha512::new();
+            hasher.update(&message);
+            assert_eq!(hash(&message), hasher.finalize());
+        }
+    }
+
+    #[test]
+    fn test_streaming_sha512() {
+        let message = b"a".repeat(1000);
+        let mut hasher = Sha512::new();
+        for piece in message.chunks(7) {
+            hasher.update(piece);
+        }
+        assert_eq!(hasher.finalize(), hash(&message));
+    }
+
+    #[test]
+    fn test_hash_vectored_sha512() {
+        assert_eq!(hash_vectored(&[b"hello ", b"world"]), hash(b"hello world"));
+    }
+
+    #[test]
+    fn test_hash_iter_sha512() {
+        let bytes: Vec<u8> = (0u8..=255).cycle().take(1000).collect();
+        assert_eq!(hash_iter((0u8..=255).cycle().take(1000)), hash(&bytes));
+        assert_eq!(hash_iter(std::iter::empty()), hash(b""));
+    }
+
+    #[test]
+    fn test_partial_blocks_sha512() {
+        // 3.5 blocks, fed in ways that hit both the buffered and the direct path
+        let message: Vec<u8> = (0..BLOCK_SIZE * 7 / 2).map(|i| i as u8).collect();
+        for split in [0, 1, BLOCK_SIZE / 2, BLOCK_SIZE, BLOCK_SIZE + 3, message.len()] {
+            let mut hasher = Sha512::new();
+            hasher.update(&message[..split]);
+            hasher.update(&message[split..]);
+            assert_eq!(hasher.finalize(), hash(&message));
+        }
+    }
+
+    #[test]
+    fn test_to_bytes_le_sha512() {
+        let hash = hash(b"hello world");
+        let be = state_to_bytes(&hash);
+        let le = state_to_bytes_le(&hash);
+        for (be_word, le_word) in be.chunks(8).zip(le.chunks(8)) {
+            let mut reversed = be_word.to_vec();
+            reversed.reverse();
+            assert_eq!(le_word, &reversed[..]);
+        }
+    }
+
+    #[test]
+    fn test_state_bytes_round_trip_sha512() {
+        let state = hash(b"hello world");
+        let bytes = hash_bytes(b"hello world");
+        assert_eq!(state_to_bytes(&state), bytes);
+        assert_eq!(bytes_to_state(&bytes), state);
+        assert_eq!(bytes_to_state(&state_to_bytes(&H)), H);
+    }
+
+    #[test]
+    fn test_digest_type_sha512() {
+        let bytes = hash_bytes(b"abc");
+        let hex = "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+                   2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f";
+
+        let digest: Sha512Digest = bytes.into();
+        assert_eq!(digest, Sha512Digest(bytes));
+        let back: [u8; 64] = digest.into();
+        assert_eq!(back, bytes);
+
+        assert_eq!(digest.to_string(), hex);
+        assert_eq!(hex.parse::<Sha512Digest>().unwrap(), digest);
+        assert!(matches!(hex[..64].parse::<Sha512Digest>(), Err(ShaError::InvalidHex)));
+        assert_eq!(Sha512Digest::try_from(&bytes[..]).unwrap(), digest);
+        assert!(matches!(Sha512Digest::try_from(&bytes[..32]), Err(ShaError::InvalidLength)));
+        assert_eq!(digest, bytes);
+    }
+
+    #[test]
+    fn test_finalize_into_sha512() {
+        let mut hasher = Sha512::new();
+        hasher.update(b"hello world");
+        let mut out = [0u8; 64];
+        hasher.clone().finalize_into(&mut out).unwrap();
+        assert_eq!(out, state_to_bytes(&hash(b"hello world")));
+
+        let mut short = [0u8; 63];
+        assert!(matches!(hasher.finalize_into(&mut short), Err(ShaError::InvalidLength)));
+    }
+
+    #[test]
+    fn test_finalize_verify_sha512() {
+        let mut expected = hash_bytes(b"hello world");
+        let mut hasher = Sha512::new();
+        hasher.update(b"hello world");
+        assert!(hasher.clone().finalize_verify(&expected));
+        assert!(!hasher.clone().finalize_verify(&expected[1..]));
+        expected[5] ^= 0x01;
+        assert!(!hasher.finalize_verify(&expected));
+    }
+
+    #[test]
+    fn test_digest_info_sha512() {
+        let digest = hash_bytes(b"abc");
+        let info = digest_info(&digest);
+        // the outer SEQUENCE length covers everything after its 2 byte header
+        assert_eq!(info[1] as usize, info.len() - 2);
+        assert_eq!(&info[19..], &digest[..]);
+        assert_eq!(&DIGEST_INFO_PREFIX[4..4 + OID.len()], &OID[..]);
+    }
+
+    #[test]
+    fn test_finalize_reset_sha512() {
+        let mut hasher = Sha512::new();
+        hasher.update(b"hello ");
+        hasher.update(b"world");
+        assert_eq!(hasher.finalize_reset(), hash(b"hello world"));
+
+        hasher.update(b"abc");
+        assert_eq!(hasher.finalize_reset(), hash(b"abc"));
+    }
+
+    #[test]
+    fn test_digest_sha512() {
+        let mut hasher = Sha512::new();
+        hasher.update(b"ab");
+        assert_eq!(hasher.digest(), hash(b"ab"));
+
+        hasher.update(b"c");
+        assert_eq!(hasher.finalize(), hash(b"abc"));
+    }
+
+    #[test]
+    fn test_bytes_processed_sha512() {
+        let mut hasher = Sha512::new();
+        assert_eq!(hasher.bytes_processed(), 0);
+        hasher.update(&[0u8; 100]);
+        hasher.update(&[0u8; 50]);
+        assert_eq!(hasher.bytes_processed(), 150);
+    }
+
+    #[test]
+    fn test_many_updates_sha512() {
+        // the byte count lives in a u128 and only becomes bits at finalize, make sure it adds up over lots of calls
+        let message: Vec<u8> = (0..300_000 * 8).map(|i: u32| (i % 251) as u8).collect();
+        let mut hasher = Sha512::new();
+        let mut offset = 0;
+        for i in 0..300_000 {
+            let len = i % 17;
+            hasher.update(&message[offset..offset + len]);
+            offset += len;
+        }
+        assert_eq!(hasher.bytes_processed(), offset as u128);
+        assert_eq!(hasher.finalize(), hash(&message[..offset]));
+    }
+
+    #[test]
+    fn test_finalize_with_len_sha512() {
+        let mut hasher = Sha512::new();
+        hasher.update(&[0x61; 100]);
+        hasher.update(&[0x61; 50]);
+        assert_eq!(hasher.finalize_with_len(), (hash_bytes(&[0x61; 150]), 150));
+    }
+
+    #[test]
+    fn test_chain_sha512() {
+        assert_eq!(Sha512::new().chain(b"a").chain(b"b").finalize(), hash(b"ab"));
+        assert_eq!(Sha512::new().chain(b"").finalize(), hash(b""));
+    }
+
+    #[test]
+    fn test_update_str_sha512() {
+        let mut hasher = Sha512::new();
+        for part in ["héllo", " ", "wörld", "", " ✓"] {
+            hasher.update_str(part);
+        }
+        assert_eq!(hasher.finalize(), hash("héllo wörld ✓".as_bytes()));
+    }
+
+    #[test]
+    fn test_update_len_prefixed_sha512() {
+        let fields = |a: &[u8], b: &[u8]| {
+            let mut hasher = Sha512::new();
+            hasher.update_len_prefixed(a);
+            hasher.update_len_prefixed(b);
+            hasher.finalize()
+        };
+        assert_ne!(fields(b"ab", b"c"), fields(b"a", b"bc"));
+        assert_eq!(fields(b"ab", b"c"), hash(b"\0\0\0\0\0\0\0\x02ab\0\0\0\0\0\0\0\x01c"));
+    }
+
+    #[test]
+    fn test_new_with_prefix_sha512() {
+        let mut manual = Sha512::new();
+        manual.update(b"prefix:");
+        manual.update(b"data");
+        let mut prefixed = Sha512::new_with_prefix(b"prefix:");
+        prefixed.update(b"data");
+        assert_eq!(prefixed.finalize(), manual.finalize());
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,46 @@
+// JavaScript bindings, build with
+// `cargo rustc --lib --target wasm32-unknown-unknown --no-default-features --features wasm --crate-type cdylib`
+// (see the README for the `wasm-bindgen` step)
+use wasm_bindgen::prelude::*;
+
+use crate::digest::{encode_hex, Digest};
+use crate::sha1::Sha1;
+use crate::sha256::Sha256;
+use crate::sha512::Sha512;
+
+fn hex_digest<D: Digest>(input: &[u8]) -> String {
+    let mut hasher = D::new();
+    hasher.update(input);
+    encode_hex(hasher.finalize_bytes().as_ref())
+}
+
+/// SHA-1 of `input` (a `Uint8Array` on the JavaScript side) as a lowercase hex string
+#[wasm_bindgen]
+pub fn sha1_hex(input: &[u8]) -> String {
+    hex_digest::<Sha1>(input)
+}
+
+/// SHA-256 of `input` (a `Uint8Array` on the JavaScript side) as a lowercase hex string
+#[wasm_bindgen]
+pub fn sha256_hex(input: &[u8]) -> String {
+    hex_digest::<Sha256>(input)
+}
+
+/// SHA-512 of `input` (a `Uint8Array` on the JavaScript side) as a lowercase hex string
+#[wasm_bindgen]
+pub fn sha512_hex(input: &[u8]) -> String {
+    hex_digest::<Sha512>(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sha1, sha256, sha512};
+
+    #[test]
+    fn hex_functions_test() {
+        assert_eq!(sha1_hex(b"hello world"), sha1::hash_hex(b"hello world"));
+        assert_eq!(sha256_hex(b"hello world"), sha256::hash_hex(b"hello world"));
+        assert_eq!(sha512_hex(b"hello world"), sha512::hash_hex(b"hello world"));
+    }
+}
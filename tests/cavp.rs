@@ -0,0 +1,107 @@
+// runs every NIST CAVP (SHAVS) .rsp file in tests/vectors, picking the algorithm and test type from the file name
+// like the official files do: SHA256ShortMsg.rsp, SHA256LongMsg.rsp, SHA256Monte.rsp
+// The files are the byte oriented ones from NIST's shabytetestvectors.zip, unchanged
+// (https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program/secure-hashing)
+use std::path::{Path, PathBuf};
+
+fn vectors_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("vectors")
+}
+
+fn decode_hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn digest(algorithm: &str, message: &[u8]) -> Vec<u8> {
+    match algorithm {
+        "SHA1" => sha::sha1::hash_bytes(message).to_vec(),
+        "SHA256" => sha::sha256::hash_bytes(message).to_vec(),
+        "SHA512" => sha::sha512::hash_bytes(message).to_vec(),
+        _ => panic!("no algorithm called {}", algorithm),
+    }
+}
+
+/// The `name = value` pairs of a .rsp file in order, without comments and `[L = ..]` headers
+fn entries(contents: &str) -> Vec<(&str, &str)> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('['))
+        .map(|line| {
+            let (name, value) = line.split_once('=').expect("line without '='");
+            (name.trim(), value.trim())
+        })
+        .collect()
+}
+
+/// ShortMsg/LongMsg: `Len` (in bits), `Msg`, `MD` triples. `Msg` is `00` for the empty message, so `Len` decides
+fn run_messages(algorithm: &str, contents: &str) -> usize {
+    let entries = entries(contents);
+    let mut count = 0;
+    for triple in entries.chunks(3) {
+        let [("Len", len), ("Msg", msg), ("MD", md)] = triple else {
+            panic!("expected Len, Msg, MD but got {:?}", triple);
+        };
+        let len: usize = len.parse().unwrap();
+        let message = &decode_hex(msg)[..len / 8];
+        assert_eq!(digest(algorithm, message), decode_hex(md), "{} Len = {}", algorithm, len);
+        count += 1;
+    }
+    count
+}
+
+/// Monte Carlo: starting from `Seed`, each checkpoint is 1000 hashes deep, every hash being over the previous three digests
+/// MD0 = MD1 = MD2 = Seed, MDi = SHA(MDi-3 || MDi-2 || MDi-1) for i in 3..1003, and MD1002 is both the
+/// checkpoint `MD` and the next `Seed`
+fn run_monte(algorithm: &str, contents: &str) -> usize {
+    let entries = entries(contents);
+    let ("Seed", seed) = entries[0] else {
+        panic!("Monte Carlo file doesn't start with a Seed");
+    };
+    let mut seed = decode_hex(seed);
+    let mut count = 0;
+    for pair in entries[1..].chunks(2) {
+        let [("COUNT", _), ("MD", expected)] = pair else {
+            panic!("expected COUNT, MD but got {:?}", pair);
+        };
+        let mut md = [seed.clone(), seed.clone(), seed];
+        for _ in 3..1003 {
+            let next = digest(algorithm, &md.concat());
+            md = [md[1].clone(), md[2].clone(), next];
+        }
+        seed = md[2].clone();
+        assert_eq!(seed, decode_hex(expected), "{} Monte COUNT = {}", algorithm, count);
+        count += 1;
+    }
+    count
+}
+
+#[test]
+fn cavp_vectors() {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(vectors_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rsp"))
+        .collect();
+    files.sort();
+    assert!(files.iter().any(|path| path.ends_with("SHA256ShortMsg.rsp")));
+
+    for path in files {
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let algorithm = ["SHA1", "SHA256", "SHA512"]
+            .into_iter()
+            .find(|alg| name.strip_prefix(alg).is_some_and(|kind| kind.chars().next().is_some_and(char::is_alphabetic)))
+            .unwrap_or_else(|| panic!("can't tell the algorithm of {}", name));
+
+        let count = match &name[algorithm.len()..] {
+            "ShortMsg" | "LongMsg" => run_messages(algorithm, &contents),
+            "Monte" => run_monte(algorithm, &contents),
+            kind => panic!("unknown CAVP test type {}", kind),
+        };
+        assert!(count > 0, "{} has no vectors", name);
+    }
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "std")]
+use sha::sha256::{hash_bytes, hash_dir, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn temp_tree(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("sha-hash-dir-test-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    root
+}
+
+fn write(root: &Path, relative: &str, contents: &[u8]) {
+    let path = root.join(relative);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+const FILES: [(&str, &[u8]); 4] = [
+    ("b.txt", b"bee"),
+    ("a/z.txt", b"zed"),
+    ("a.txt", b"ay"),
+    ("a/deep/x", b""),
+];
+
+#[test]
+fn hash_dir_independent_of_creation_order() {
+    let forward = temp_tree("forward");
+    let backward = temp_tree("backward");
+    for (path, contents) in FILES {
+        write(&forward, path, contents);
+    }
+    for (path, contents) in FILES.iter().rev() {
+        write(&backward, path, contents);
+    }
+    // empty directories don't count
+    fs::create_dir(backward.join("empty")).unwrap();
+
+    let forward_digest = hash_dir(&forward).unwrap();
+    let backward_digest = hash_dir(&backward).unwrap();
+
+    // sorted by path bytes: "a.txt" < "a/deep/x" < "a/z.txt" < "b.txt"
+    let mut expected = Sha256::new();
+    for (path, contents) in [("a.txt", &b"ay"[..]), ("a/deep/x", b""), ("a/z.txt", b"zed"), ("b.txt", b"bee")] {
+        expected.update(b"f");
+        expected.update_len_prefixed(path.as_bytes());
+        expected.update(&hash_bytes(contents));
+    }
+
+    write(&forward, "a/z.txt", b"zed!");
+    let changed = hash_dir(&forward).unwrap();
+    fs::remove_dir_all(&forward).unwrap();
+    fs::remove_dir_all(&backward).unwrap();
+
+    assert_eq!(forward_digest, backward_digest);
+    assert_eq!(forward_digest, sha::sha256::state_to_bytes(&expected.finalize()));
+    assert_ne!(changed, forward_digest);
+}
+
+#[test]
+fn hash_dir_moved_file() {
+    let first = temp_tree("moved-first");
+    let second = temp_tree("moved-second");
+    write(&first, "a/bc", b"same");
+    write(&second, "ab/c", b"same");
+
+    let first_digest = hash_dir(&first).unwrap();
+    let second_digest = hash_dir(&second).unwrap();
+    fs::remove_dir_all(&first).unwrap();
+    fs::remove_dir_all(&second).unwrap();
+
+    assert_ne!(first_digest, second_digest);
+}
+
+#[cfg(unix)]
+#[test]
+fn hash_dir_symlink_not_followed() {
+    let root = temp_tree("symlink");
+    write(&root, "target.txt", b"contents");
+    std::os::unix::fs::symlink("target.txt", root.join("link")).unwrap();
+    std::os::unix::fs::symlink("nowhere", root.join("dangling")).unwrap();
+
+    let digest = hash_dir(&root).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+
+    let mut expected = Sha256::new();
+    expected.update(b"l");
+    expected.update_len_prefixed(b"dangling");
+    expected.update_len_prefixed(b"nowhere");
+    expected.update(b"l");
+    expected.update_len_prefixed(b"link");
+    expected.update_len_prefixed(b"target.txt");
+    expected.update(b"f");
+    expected.update_len_prefixed(b"target.txt");
+    expected.update(&hash_bytes(b"contents"));
+    assert_eq!(digest, sha::sha256::state_to_bytes(&expected.finalize()));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn hash_dir_non_utf8_path() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let root = temp_tree("non-utf8");
+    fs::write(root.join(std::ffi::OsStr::from_bytes(b"bad-\xff")), b"x").unwrap();
+
+    let result = hash_dir(&root);
+    fs::remove_dir_all(&root).unwrap();
+
+    match result {
+        Err(sha::error::ShaError::Io(err)) => assert_eq!(err.kind(), std::io::ErrorKind::InvalidData),
+        other => panic!("expected an InvalidData error, got {:?}", other),
+    }
+}
+
+#[test]
+fn hash_dir_missing() {
+    let path = std::env::temp_dir().join("sha-hash-dir-test-does-not-exist");
+    assert!(matches!(hash_dir(path), Err(sha::error::ShaError::Io(_))));
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "mmap")]
+use sha::sha256::{hash_bytes, hash_mmap};
+
+fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("sha-mmap-test-{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn hash_mmap_matches_hash_bytes() {
+    let contents = "abc".repeat(50_000);
+    let path = temp_file("abc", contents.as_bytes());
+    let digest = hash_mmap(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(digest.unwrap(), hash_bytes(contents.as_bytes()));
+}
+
+#[test]
+fn hash_mmap_empty_file() {
+    let path = temp_file("empty", b"");
+    let digest = hash_mmap(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(digest.unwrap(), hash_bytes(b""));
+}
+
+#[test]
+fn hash_mmap_missing_file() {
+    let path = std::env::temp_dir().join("sha-mmap-test-does-not-exist");
+    assert!(matches!(hash_mmap(path), Err(sha::error::ShaError::Io(_))));
+}
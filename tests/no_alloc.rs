@@ -0,0 +1,57 @@
+// checks that the streaming hashers never touch the heap, using a global allocator that counts
+// allocations made on the current thread while counting is switched on
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.with(|c| c.get()) {
+            ALLOCATIONS.with(|a| a.set(a.get() + 1));
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Run `f` and return how many allocations it made
+fn allocations_in(f: impl FnOnce()) -> usize {
+    ALLOCATIONS.with(|a| a.set(0));
+    COUNTING.with(|c| c.set(true));
+    f();
+    COUNTING.with(|c| c.set(false));
+    ALLOCATIONS.with(|a| a.get())
+}
+
+#[test]
+fn streaming_hashers_do_not_allocate() {
+    let message = [0x61u8; 1000];
+
+    let allocations = allocations_in(|| {
+        let mut sha1 = sha::sha1::Sha1::new();
+        let mut sha256 = sha::sha256::Sha256::new();
+        let mut sha512 = sha::sha512::Sha512::new();
+        for piece in message.chunks(37) {
+            sha1.update(piece);
+            sha256.update(piece);
+            sha512.update(piece);
+        }
+        std::hint::black_box((sha1.finalize(), sha256.finalize(), sha512.finalize()));
+    });
+
+    assert_eq!(allocations, 0);
+    // make sure the counter actually works
+    assert!(allocations_in(|| drop(std::hint::black_box(vec![0u8; 10]))) > 0);
+}
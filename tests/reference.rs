@@ -0,0 +1,33 @@
+// differential tests against the RustCrypto sha1/sha2 crates
+use proptest::prelude::*;
+use sha2::Digest;
+
+/// Random messages, weighted towards the lengths where padding needs an extra block
+fn messages() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        Just(Vec::new()),
+        prop::collection::vec(any::<u8>(), 55..=57),
+        prop::collection::vec(any::<u8>(), 119..=129),
+        prop::collection::vec(any::<u8>(), 0..1024),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn sha1_matches_reference(message in messages()) {
+        let ours = sha::sha1::hash_bytes(&message);
+        prop_assert_eq!(&ours[..], &sha1::Sha1::digest(&message)[..]);
+    }
+
+    #[test]
+    fn sha256_matches_reference(message in messages()) {
+        let ours = sha::sha256::hash_bytes(&message);
+        prop_assert_eq!(&ours[..], &sha2::Sha256::digest(&message)[..]);
+    }
+
+    #[test]
+    fn sha512_matches_reference(message in messages()) {
+        let ours = sha::sha512::hash_bytes(&message);
+        prop_assert_eq!(&ours[..], &sha2::Sha512::digest(&message)[..]);
+    }
+}
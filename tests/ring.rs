@@ -0,0 +1,35 @@
+// differential test against ring's SHA-256 over the lengths where padding goes wrong most easily
+const LENGTHS: [usize; 10] = [0, 1, 55, 56, 57, 63, 64, 65, 128, 1024 * 1024];
+
+fn message(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 31 + 7) as u8).collect()
+}
+
+#[test]
+fn sha256_matches_ring() {
+    for len in LENGTHS {
+        let message = message(len);
+        let ours = sha::sha256::hash_bytes(&message);
+        let theirs = ring::digest::digest(&ring::digest::SHA256, &message);
+        assert_eq!(&ours[..], theirs.as_ref(), "length {}", len);
+    }
+}
+
+#[test]
+fn sha256_streaming_matches_ring() {
+    for len in LENGTHS {
+        let message = message(len);
+        let mut ours = sha::sha256::Sha256::new();
+        let mut theirs = ring::digest::Context::new(&ring::digest::SHA256);
+        for piece in message.chunks(1000) {
+            ours.update(piece);
+            theirs.update(piece);
+        }
+        assert_eq!(
+            &sha::sha256::state_to_bytes(&ours.finalize())[..],
+            theirs.finish().as_ref(),
+            "length {}",
+            len
+        );
+    }
+}
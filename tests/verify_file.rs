@@ -0,0 +1,26 @@
+#![cfg(feature = "std")]
+use sha::sha256::{hash_bytes, verify_file};
+
+#[test]
+fn verify_file_matching_and_not() {
+    let contents = "abc".repeat(10_000);
+    let path = std::env::temp_dir().join(format!("sha-verify-file-test-{}", std::process::id()));
+    std::fs::write(&path, &contents).unwrap();
+
+    let mut expected = hash_bytes(contents.as_bytes());
+    let matching = verify_file(&path, &expected);
+    let short = verify_file(&path, &expected[..31]);
+    expected[0] ^= 1;
+    let wrong = verify_file(&path, &expected);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matching.unwrap());
+    assert!(!short.unwrap());
+    assert!(!wrong.unwrap());
+}
+
+#[test]
+fn verify_file_missing() {
+    let path = std::env::temp_dir().join("sha-verify-file-test-does-not-exist");
+    assert!(matches!(verify_file(path, &[0; 32]), Err(sha::error::ShaError::Io(_))));
+}